@@ -3,7 +3,13 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
+use swaps::msg::{
+    CollectionStatsResponse, CollectionsResponse, ConfigResponse, EscrowedTokenResponse,
+    ExecuteMsg, GetSwapsResponse, HtlcResponse, InboxResponse, InstantiateMsg, ListSwapsResponse,
+    MigrateMsg, NonceStatusResponse, NoncesResponse, PendingClaimsResponse, PreimageResponse,
+    QueryMsg, ReconcileResponse, SimulateResponse, StatsResponse, SwapDetailsResponse,
+    SwapPermissionsResponse, SwapResponse,
+};
 use swaps::state::{Config, Swap};
 
 fn main() {
@@ -15,7 +21,25 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(Swap), &out_dir);
     export_schema(&schema_for!(SwapResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(CollectionsResponse), &out_dir);
+    export_schema(&schema_for!(ListSwapsResponse), &out_dir);
+    export_schema(&schema_for!(InboxResponse), &out_dir);
+    export_schema(&schema_for!(EscrowedTokenResponse), &out_dir);
+    export_schema(&schema_for!(SwapDetailsResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapsResponse), &out_dir);
+    export_schema(&schema_for!(SwapPermissionsResponse), &out_dir);
+    export_schema(&schema_for!(SimulateResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(CollectionStatsResponse), &out_dir);
+    export_schema(&schema_for!(PendingClaimsResponse), &out_dir);
+    export_schema(&schema_for!(NoncesResponse), &out_dir);
+    export_schema(&schema_for!(NonceStatusResponse), &out_dir);
+    export_schema(&schema_for!(HtlcResponse), &out_dir);
+    export_schema(&schema_for!(PreimageResponse), &out_dir);
+    export_schema(&schema_for!(ReconcileResponse), &out_dir);
 }
@@ -1,11 +1,24 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
 };
 
 use crate::error::ContractError;
-use crate::executions::{cancel_swap, finalize_swap, initialize, initiate_swap, swap_reply};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::queries::get_swap;
+use crate::executions::{
+    batch, cancel_all, cancel_nonces, cancel_swap, claim, claim_htlc, execute_signed_offer,
+    finalize_swap, increment_min_nonce, initialize, initiate_swap, lock_htlc, reclaim_expired_swap,
+    refund_all, refund_htlc, reject_offer, retry_settlement, settlement_failed, shutdown,
+    swap_reply, unwind_settlement, update_allowed_collections, update_collection_mode,
+    update_denied_collections, update_payout_mode, withdraw_offer,
+};
+use crate::migrations::migrate_state;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::permissions::ensure_nonpayable;
+use crate::queries::{
+    allowed_collections, denied_collections, get_collection_stats, get_config, get_escrowed_token,
+    get_htlc, get_preimage, get_stats, get_swap, get_swap_details, get_swaps, inbox, list_swaps,
+    nonce_status, nonces, pending_claims, reconcile, swap_permissions,
+};
+use crate::simulation::simulate;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -24,24 +37,140 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    ensure_nonpayable(&info)?;
     match msg {
         ExecuteMsg::InitiateSwap {
             collection,
             token_id,
-        } => initiate_swap(deps, env, info, collection, token_id),
+            expires,
+            custody,
+        } => initiate_swap(deps, env, info, collection, token_id, expires, custody),
         ExecuteMsg::SwapReply {
             swap_id,
             collection,
             token_id,
         } => swap_reply(deps, env, info, swap_id, collection, token_id),
         ExecuteMsg::FinalizeSwap { swap_id } => finalize_swap(deps, env, info, swap_id),
+        ExecuteMsg::RejectOffer { swap_id } => reject_offer(deps, env, info, swap_id),
+        ExecuteMsg::WithdrawOffer { swap_id } => withdraw_offer(deps, env, info, swap_id),
+        ExecuteMsg::RetrySettlement { swap_id } => retry_settlement(deps, env, info, swap_id),
+        ExecuteMsg::UnwindSettlement { swap_id } => unwind_settlement(deps, env, info, swap_id),
+        ExecuteMsg::ExecuteSignedOffer {
+            offer,
+            token_id,
+            signature,
+            pubkey,
+        } => execute_signed_offer(deps, env, info, offer, token_id, signature, pubkey),
+        ExecuteMsg::CancelNonces { nonces } => cancel_nonces(deps, env, info, nonces),
+        ExecuteMsg::IncrementMinNonce { min_nonce } => {
+            increment_min_nonce(deps, env, info, min_nonce)
+        }
+        ExecuteMsg::LockHtlc {
+            collection,
+            token_id,
+            recipient,
+            hashlock,
+            expires,
+        } => lock_htlc(
+            deps, env, info, collection, token_id, recipient, hashlock, expires,
+        ),
+        ExecuteMsg::ClaimHtlc { htlc_id, preimage } => {
+            claim_htlc(deps, env, info, htlc_id, preimage)
+        }
+        ExecuteMsg::RefundHtlc { htlc_id } => refund_htlc(deps, env, info, htlc_id),
+        ExecuteMsg::CancelAll { limit } => cancel_all(deps, env, info, limit),
         ExecuteMsg::CancelSwap { swap_id } => cancel_swap(deps, env, info, swap_id),
+        ExecuteMsg::ReclaimExpiredSwap { swap_id } => {
+            reclaim_expired_swap(deps, env, info, swap_id)
+        }
+        ExecuteMsg::Shutdown {} => shutdown(deps, env, info),
+        ExecuteMsg::RefundAll { limit } => refund_all(deps, env, info, limit),
+        ExecuteMsg::UpdateCollectionMode { mode } => update_collection_mode(deps, env, info, mode),
+        ExecuteMsg::UpdatePayoutMode { mode } => update_payout_mode(deps, env, info, mode),
+        ExecuteMsg::Claim { limit } => claim(deps, env, info, limit),
+        ExecuteMsg::UpdateAllowedCollections { add, remove } => {
+            update_allowed_collections(deps, env, info, add, remove)
+        }
+        ExecuteMsg::UpdateDeniedCollections { add, remove } => {
+            update_denied_collections(deps, env, info, add, remove)
+        }
+        ExecuteMsg::Batch { msgs } => batch(deps, env, info, msgs),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    settlement_failed(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetSwap { swap_id } => to_binary(&get_swap(deps, swap_id)?),
+        QueryMsg::SwapPermissions { swap_id, viewer } => {
+            to_binary(&swap_permissions(deps, env, swap_id, viewer)?)
+        }
+        QueryMsg::Simulate { sender, funds, msg } => {
+            to_binary(&simulate(deps, env, sender, funds, *msg)?)
+        }
+        QueryMsg::Stats {} => to_binary(&get_stats(deps)?),
+        QueryMsg::CollectionStats { collection } => {
+            to_binary(&get_collection_stats(deps, collection)?)
+        }
+        QueryMsg::GetSwaps { ids } => to_binary(&get_swaps(deps, ids)?),
+        QueryMsg::GetSwapDetails { swap_id } => to_binary(&get_swap_details(deps, swap_id)?),
+        QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+        QueryMsg::PendingClaims {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&pending_claims(deps, address, start_after, limit)?),
+        QueryMsg::Nonces {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&nonces(deps, address, start_after, limit)?),
+        QueryMsg::NonceStatus { address, nonce } => to_binary(&nonce_status(deps, address, nonce)?),
+        QueryMsg::Htlc { htlc_id } => to_binary(&get_htlc(deps, htlc_id)?),
+        QueryMsg::Preimage { hashlock } => to_binary(&get_preimage(deps, hashlock)?),
+        QueryMsg::Reconcile {
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&reconcile(deps, env, collection, start_after, limit)?),
+        QueryMsg::AllowedCollections { start_after, limit } => {
+            to_binary(&allowed_collections(deps, start_after, limit)?)
+        }
+        QueryMsg::DeniedCollections { start_after, limit } => {
+            to_binary(&denied_collections(deps, start_after, limit)?)
+        }
+        QueryMsg::ListSwaps {
+            start_after,
+            limit,
+            status,
+            owner,
+            collection,
+        } => to_binary(&list_swaps(
+            deps,
+            start_after,
+            limit,
+            status,
+            owner,
+            collection,
+        )?),
+        QueryMsg::Inbox {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&inbox(deps, env, address, start_after, limit)?),
+        QueryMsg::EscrowedToken {
+            collection,
+            token_id,
+        } => to_binary(&get_escrowed_token(deps, collection, token_id)?),
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    migrate_state(deps, env, msg)
+}
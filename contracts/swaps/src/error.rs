@@ -1,3 +1,4 @@
+use crate::state::{HtlcStatus, SwapAction, SwapStatus};
 use cosmwasm_std::StdError;
 use thiserror::Error;
 
@@ -9,12 +10,135 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("NonPayable")]
+    NonPayable {},
+
     #[error("InvalidCollectionAddress")]
     InvalidAddress { address: String },
 
+    #[error("InvalidAccountAddress")]
+    InvalidAccountAddress { address: String },
+
     #[error("SwapNotFound")]
     SwapNotFound {},
 
     #[error("SwapNotResponded")]
     SwapNotResponded {},
+
+    #[error("ContractShutdown")]
+    ContractShutdown {},
+
+    #[error("ContractNotShutdown")]
+    ContractNotShutdown {},
+
+    #[error("CollectionNotAllowed")]
+    CollectionNotAllowed { collection: String },
+
+    #[error("CannotMigrate")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("CannotDowngrade")]
+    CannotDowngrade {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("MigrationAdminRequired")]
+    MigrationAdminRequired {},
+
+    #[error("InvalidSwapId")]
+    InvalidSwapId { swap_id: String },
+
+    #[error("InvalidSwapTransition")]
+    InvalidSwapTransition {
+        status: SwapStatus,
+        action: SwapAction,
+    },
+
+    #[error("SwapExpired")]
+    SwapExpired {},
+
+    #[error("SwapNotExpired")]
+    SwapNotExpired {},
+
+    #[error("TokenAlreadyEscrowed")]
+    TokenAlreadyEscrowed { swap_id: u64 },
+
+    #[error("TokenNotFound")]
+    TokenNotFound {
+        collection: String,
+        token_id: String,
+    },
+
+    #[error("NotTokenOwner")]
+    NotTokenOwner {
+        collection: String,
+        token_id: String,
+    },
+
+    #[error("ContractNotApproved")]
+    ContractNotApproved {
+        collection: String,
+        token_id: String,
+    },
+
+    #[error("NotCw721Contract")]
+    NotCw721Contract { collection: String },
+
+    #[error("SettlementPartiallyDelivered")]
+    SettlementPartiallyDelivered {},
+
+    #[error("NothingToClaim")]
+    NothingToClaim {},
+
+    #[error("WrongContract")]
+    WrongContract {},
+
+    #[error("NotWantedToken")]
+    NotWantedToken {},
+
+    #[error("SignerMismatch")]
+    SignerMismatch {},
+
+    #[error("InvalidSignature")]
+    InvalidSignature {},
+
+    #[error("NonceUsed")]
+    NonceUsed { nonce: u64 },
+
+    #[error("NonceBelowMinimum")]
+    NonceBelowMinimum { nonce: u64, min_nonce: u64 },
+
+    #[error("MinNonceNotIncreased")]
+    MinNonceNotIncreased { min_nonce: u64 },
+
+    #[error("HtlcNotFound")]
+    HtlcNotFound {},
+
+    #[error("InvalidHashlock")]
+    InvalidHashlock {},
+
+    #[error("InvalidPreimage")]
+    InvalidPreimage {},
+
+    #[error("HtlcTimelockRequired")]
+    HtlcTimelockRequired {},
+
+    #[error("HtlcExpired")]
+    HtlcExpired {},
+
+    #[error("HtlcNotExpired")]
+    HtlcNotExpired {},
+
+    #[error("HtlcNotLocked")]
+    HtlcNotLocked { status: HtlcStatus },
+
+    #[error("BatchTooLarge")]
+    BatchTooLarge { max: u32 },
+
+    #[error("NestedBatch")]
+    NestedBatch {},
+
+    #[error("UnknownReplyId")]
+    UnknownReplyId { id: u64 },
 }
@@ -0,0 +1,90 @@
+use cosmwasm_std::{Binary, Event};
+
+use crate::state::{Htlc, Swap};
+
+/// Lifecycle events, emitted by the chain as `wasm-<name>`.
+///
+/// Every event carries the same attributes: `swap_id`, `status`, then `owner`, `collection`
+/// and `token_id` for both sides, prefixed with `lhs_` and `rhs_`. The rhs attributes are
+/// empty while the swap has no offer.
+pub enum SwapEvent {
+    Created,
+    OfferMade,
+    OfferRejected,
+    OfferWithdrawn,
+    Settled,
+    SettlementFailed,
+    Cancelled,
+    Expired,
+    Broken,
+}
+
+impl SwapEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            SwapEvent::Created => "swap_created",
+            SwapEvent::OfferMade => "offer_made",
+            SwapEvent::OfferRejected => "offer_rejected",
+            SwapEvent::OfferWithdrawn => "offer_withdrawn",
+            SwapEvent::Settled => "swap_settled",
+            SwapEvent::SettlementFailed => "swap_settlement_failed",
+            SwapEvent::Cancelled => "swap_cancelled",
+            SwapEvent::Expired => "swap_expired",
+            SwapEvent::Broken => "swap_broken",
+        }
+    }
+
+    pub fn build(&self, swap_id: u64, swap: &Swap) -> Event {
+        let (rhs_owner, rhs_collection, rhs_token_id) = match &swap.rhs {
+            Some(rhs) => (
+                rhs.owner.to_string(),
+                rhs.collection.to_string(),
+                rhs.token_id.clone(),
+            ),
+            None => (String::new(), String::new(), String::new()),
+        };
+
+        Event::new(self.name())
+            .add_attribute("swap_id", swap_id.to_string())
+            .add_attribute("status", swap.status.as_str())
+            .add_attribute("lhs_owner", &swap.lhs.owner)
+            .add_attribute("lhs_collection", &swap.lhs.collection)
+            .add_attribute("lhs_token_id", &swap.lhs.token_id)
+            .add_attribute("rhs_owner", rhs_owner)
+            .add_attribute("rhs_collection", rhs_collection)
+            .add_attribute("rhs_token_id", rhs_token_id)
+    }
+}
+
+/// HTLC lifecycle events, carrying `htlc_id`, `status`, `depositor`, `recipient`,
+/// `collection`, `token_id`, `hashlock` and `preimage`, which stays empty until claimed.
+pub enum HtlcEvent {
+    Locked,
+    Claimed,
+    Refunded,
+}
+
+impl HtlcEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            HtlcEvent::Locked => "htlc_locked",
+            HtlcEvent::Claimed => "htlc_claimed",
+            HtlcEvent::Refunded => "htlc_refunded",
+        }
+    }
+
+    pub fn build(&self, htlc_id: u64, htlc: &Htlc, preimage: Option<&Binary>) -> Event {
+        Event::new(self.name())
+            .add_attribute("htlc_id", htlc_id.to_string())
+            .add_attribute("status", htlc.status.as_str())
+            .add_attribute("depositor", &htlc.depositor)
+            .add_attribute("recipient", &htlc.recipient)
+            .add_attribute("collection", &htlc.collection)
+            .add_attribute("token_id", &htlc.token_id)
+            .add_attribute("hashlock", htlc.hashlock.to_base64())
+            .add_attribute(
+                "preimage",
+                preimage.map(Binary::to_base64).unwrap_or_default(),
+            )
+    }
+}
@@ -1,15 +1,41 @@
-use crate::state::SwapSide;
-use cosmwasm_std::{to_binary, CosmosMsg, DepsMut, Env, MessageInfo, Response, WasmMsg};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, CosmosMsg, DepsMut, Empty, Env, MessageInfo, Order, Reply,
+    Response, StdResult, Storage, SubMsg, Uint64, WasmMsg,
+};
 use cw2::set_contract_version;
 use cw721::Cw721ExecuteMsg::TransferNft;
+use cw721::Expiration;
+use cw_storage_plus::{Bound, Map};
+use sha2::{Digest, Sha256};
 
+use crate::contract::execute;
 use crate::error::ContractError;
-use crate::msg::InstantiateMsg;
-use crate::state::Config;
-use crate::state::{Swap, CONFIG, SWAPS};
+use crate::events::{HtlcEvent, SwapEvent};
+use crate::msg::{ExecuteMsg, InstantiateMsg, SignedOffer};
+use crate::permissions::{
+    self, ensure_can_accept, ensure_can_cancel, ensure_can_reclaim, ensure_can_reject,
+    ensure_can_respond, ensure_can_retry, ensure_can_unwind, ensure_can_withdraw,
+};
+use crate::preflight::ensure_can_escrow;
+use crate::signatures::verify_signed_offer;
+use crate::state::{
+    credit_claim, escrowed_token, ledger_deposit_nft, ledger_withdraw_nft, save_swap,
+    swap_id_from_key, swaps, Claimable, CollectionMode, Config, Custody, Htlc, HtlcStatus,
+    PayoutMode, Stats, Swap, SwapAction, SwapRole, SwapSide, SwapStatus, ALLOWED_COLLECTIONS,
+    CLAIMS, CONFIG, DENIED_COLLECTIONS, HTLCS, MIN_NONCES, NEXT_HTLC_ID, PREIMAGES,
+    REFUND_PROGRESS, STATS, USED_NONCES,
+};
 
-const CONTRACT_NAME: &str = "crates.io:swaps";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const CONTRACT_NAME: &str = "crates.io:swaps";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_REFUND_LIMIT: u32 = 10;
+const MAX_REFUND_LIMIT: u32 = 30;
+const DEFAULT_CLAIM_LIMIT: u32 = 10;
+const MAX_CLAIM_LIMIT: u32 = 30;
+const MAX_BATCH_SIZE: u32 = 30;
+const DEFAULT_CANCEL_ALL_LIMIT: u32 = 10;
+const MAX_CANCEL_ALL_LIMIT: u32 = 30;
 
 pub fn initialize(
     deps: DepsMut,
@@ -19,8 +45,15 @@ pub fn initialize(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let config = Config { next_swap_id: 1 };
+    let config = Config {
+        next_swap_id: 1,
+        admin: info.sender.clone(),
+        shutdown: false,
+        collection_mode: CollectionMode::Open,
+        payout_mode: PayoutMode::Push,
+    };
     CONFIG.save(deps.storage, &config)?;
+    STATS.save(deps.storage, &Stats::default())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -33,7 +66,11 @@ pub fn initiate_swap(
     info: MessageInfo,
     collection: String,
     token_id: String,
+    expires: Option<Expiration>,
+    custody: Option<Custody>,
 ) -> Result<Response, ContractError> {
+    ensure_not_shutdown(&deps)?;
+
     let collection = match deps.api.addr_validate(&collection) {
         Ok(collection) => collection,
         Err(_) => {
@@ -42,6 +79,8 @@ pub fn initiate_swap(
             })
         }
     };
+    ensure_collection_allowed(&deps, &collection)?;
+    ensure_not_escrowed(&deps, &collection, &token_id)?;
 
     let swap_id = CONFIG.load(deps.storage).unwrap().next_swap_id;
     let swap = Swap {
@@ -51,9 +90,17 @@ pub fn initiate_swap(
             token_id: token_id.clone(),
         },
         rhs: None,
+        status: SwapStatus::Open,
+        expires: expires.unwrap_or_default(),
+        failed_transfers: vec![],
+        custody: custody.unwrap_or_default(),
     };
+    if swap.is_expired(&env.block) {
+        return Err(ContractError::SwapExpired {});
+    }
+    ensure_can_escrow(&deps, &env, &info.sender, &collection, &token_id)?;
 
-    SWAPS.save(deps.storage, swap_id.to_string(), &swap)?;
+    save_swap(deps.storage, swap_id, &swap)?;
 
     CONFIG.update(deps.storage, |mut state| -> Result<_, ContractError> {
         state.next_swap_id += 1;
@@ -63,24 +110,25 @@ pub fn initiate_swap(
     Ok(Response::new()
         .add_attribute("method", "create_swap")
         .add_attribute("swap_id", swap_id.to_string())
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: swap.lhs.collection.to_string(),
-            funds: vec![],
-            msg: to_binary(&TransferNft {
-                recipient: env.contract.address.to_string(),
-                token_id: token_id.clone(),
-            })?,
-        })]))
+        .add_event(SwapEvent::Created.build(swap_id, &swap))
+        .add_messages(escrow_nft(
+            deps.storage,
+            &swap,
+            &swap.lhs,
+            &env.contract.address,
+        )?))
 }
 
 pub fn swap_reply(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    swap_id: String,
+    swap_id: Uint64,
     collection: String,
     token_id: String,
 ) -> Result<Response, ContractError> {
+    ensure_not_shutdown(&deps)?;
+
     let collection = match deps.api.addr_validate(&collection) {
         Ok(collection) => collection,
         Err(_) => {
@@ -89,86 +137,956 @@ pub fn swap_reply(
             })
         }
     };
+    ensure_collection_allowed(&deps, &collection)?;
+    ensure_not_escrowed(&deps, &collection, &token_id)?;
 
-    let reply_to_swap = |d: Option<Swap>| -> Result<Swap, ContractError> {
-        match d {
-            Some(one) => Ok(Swap {
-                lhs: one.lhs,
-                rhs: Some(SwapSide {
-                    owner: info.sender.clone(),
-                    collection: collection.clone(),
-                    token_id: token_id.clone(),
-                }),
-            }),
-            None => return Err(ContractError::SwapNotFound {}),
-        }
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_respond(&swap, &env.block)?;
+    swap.status = swap.status.transition(SwapAction::Respond)?;
+    ensure_can_escrow(&deps, &env, &info.sender, &collection, &token_id)?;
+    let rhs = SwapSide {
+        owner: info.sender,
+        collection,
+        token_id,
     };
-    let swap = SWAPS.update(deps.storage, swap_id, reply_to_swap)?;
+    swap.rhs = Some(rhs.clone());
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
 
     Ok(Response::new()
         .add_attribute("method", "swap_reply")
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: swap.rhs.unwrap().collection.to_string(),
-            funds: vec![],
-            msg: to_binary(&TransferNft {
-                recipient: env.contract.address.to_string(),
-                token_id: token_id.clone(),
-            })?,
-        })]))
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::OfferMade.build(swap_id.u64(), &swap))
+        .add_messages(escrow_nft(
+            deps.storage,
+            &swap,
+            &rhs,
+            &env.contract.address,
+        )?))
 }
 
 pub fn finalize_swap(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
-    swap_id: String,
+    info: MessageInfo,
+    swap_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let swap = match SWAPS.load(deps.storage, swap_id.clone()) {
-        Ok(swap) => swap,
-        Err(_) => {
-            return Err(ContractError::SwapNotFound {});
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_accept(&swap, &info.sender, &env.block)?;
+
+    // NFTs under approval custody may have moved or lost their approval since they were offered
+    if swap.custody == Custody::Approval {
+        let rhs = swap.rhs.as_ref().unwrap();
+        let check = ensure_can_escrow(
+            &deps,
+            &env,
+            &swap.lhs.owner,
+            &swap.lhs.collection,
+            &swap.lhs.token_id,
+        )
+        .and_then(|_| ensure_can_escrow(&deps, &env, &rhs.owner, &rhs.collection, &rhs.token_id));
+        if let Err(err) = check {
+            swap.status = swap.status.transition(SwapAction::Break)?;
+            save_swap(deps.storage, swap_id.u64(), &swap)?;
+            return Ok(Response::new()
+                .add_attribute("method", "finalize_reply")
+                .add_attribute("swap_id", swap_id.to_string())
+                .add_attribute("reason", err.to_string())
+                .add_event(SwapEvent::Broken.build(swap_id.u64(), &swap)));
         }
+    }
+    swap.status = swap.status.transition(SwapAction::Accept)?;
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "finalize_reply")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Settled.build(swap_id.u64(), &swap))
+        .add_submessages(settlement_transfers(
+            deps.storage,
+            swap_id.u64(),
+            &swap,
+            &[SwapRole::Listing, SwapRole::Offer],
+        )?))
+}
+
+/// Reply to a failed settlement transfer. Only that transfer is reverted, the rest of the
+/// settlement goes through and the swap waits for one of the parties to recover it.
+pub fn settlement_failed(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let (swap_id, role) = parse_settlement_reply_id(msg.id)?;
+    let mut swap = load_swap(&deps, swap_id.into())?;
+    swap.status = swap.status.transition(SwapAction::FailSettlement)?;
+    swap.failed_transfers.push(role);
+    save_swap(deps.storage, swap_id, &swap)?;
+    if swap.custody == Custody::Escrow {
+        let side = match role {
+            SwapRole::Listing => &swap.lhs,
+            SwapRole::Offer => swap.rhs.as_ref().unwrap(),
+        };
+        ledger_deposit_nft(deps.storage, &side.collection, &side.token_id)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "settlement_failed")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::SettlementFailed.build(swap_id, &swap)))
+}
+
+pub fn retry_settlement(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_retry(&swap, &info.sender)?;
+    swap.status = swap.status.transition(SwapAction::Retry)?;
+    let failed = std::mem::take(&mut swap.failed_transfers);
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "retry_settlement")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Settled.build(swap_id.u64(), &swap))
+        .add_submessages(settlement_transfers(
+            deps.storage,
+            swap_id.u64(),
+            &swap,
+            &failed,
+        )?))
+}
+
+pub fn unwind_settlement(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_unwind(&swap, &info.sender)?;
+    swap.status = swap.status.transition(SwapAction::Unwind)?;
+    swap.failed_transfers.clear();
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "unwind_settlement")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Cancelled.build(swap_id.u64(), &swap))
+        .add_messages(refund_swap(deps.storage, &swap)?))
+}
+
+/// Settles an offer signed off-chain by the lister. Nothing is escrowed, both NFTs are
+/// transferred from their owners' wallets in the same transaction, so the swap is either
+/// completed or not saved at all.
+pub fn execute_signed_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer: SignedOffer,
+    token_id: String,
+    signature: Binary,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    ensure_not_shutdown(&deps)?;
+
+    if offer.contract != env.contract.address.as_str() {
+        return Err(ContractError::WrongContract {});
+    }
+    if offer.expires.is_expired(&env.block) {
+        return Err(ContractError::SwapExpired {});
+    }
+    if matches!(&offer.want.token_id, Some(wanted) if *wanted != token_id) {
+        return Err(ContractError::NotWantedToken {});
+    }
+    verify_signed_offer(deps.api, &offer, &signature, &pubkey)?;
+
+    let lhs = SwapSide {
+        owner: validate_account(&deps, offer.lister.clone())?,
+        collection: validate_collection(&deps, offer.give.collection.clone())?,
+        token_id: offer.give.token_id.clone(),
+    };
+    let rhs = SwapSide {
+        owner: info.sender,
+        collection: validate_collection(&deps, offer.want.collection.clone())?,
+        token_id,
+    };
+    for side in &[&lhs, &rhs] {
+        ensure_collection_allowed(&deps, &side.collection)?;
+        ensure_not_escrowed(&deps, &side.collection, &side.token_id)?;
+        ensure_can_escrow(&deps, &env, &side.owner, &side.collection, &side.token_id)?;
+    }
+
+    use_nonce(deps.storage, &lhs.owner, offer.nonce.u64())?;
+
+    let swap_id = CONFIG.load(deps.storage)?.next_swap_id;
+    let swap = Swap {
+        lhs,
+        rhs: Some(rhs),
+        status: SwapStatus::Completed,
+        expires: offer.expires,
+        failed_transfers: vec![],
+        custody: Custody::Approval,
     };
+    save_swap(deps.storage, swap_id, &swap)?;
+
+    CONFIG.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        state.next_swap_id += 1;
+        Ok(state)
+    })?;
 
-    if swap.rhs == None {
-        return Err(ContractError::SwapNotResponded {});
+    let rhs = swap.rhs.as_ref().unwrap();
+    Ok(Response::new()
+        .add_attribute("method", "execute_signed_offer")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Settled.build(swap_id, &swap))
+        .add_message(transfer_nft(&swap.lhs, &rhs.owner)?)
+        .add_message(transfer_nft(rhs, &swap.lhs.owner)?))
+}
+
+pub fn cancel_nonces(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nonces: Vec<Uint64>,
+) -> Result<Response, ContractError> {
+    for nonce in &nonces {
+        USED_NONCES.save(deps.storage, (&info.sender, nonce.u64().into()), &Empty {})?;
     }
 
     Ok(Response::new()
-        .add_attribute("method", "finalize_reply")
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: swap.rhs.clone().unwrap().collection.to_string(),
-            funds: vec![],
-            msg: to_binary(&TransferNft {
-                recipient: env.contract.address.to_string(),
-                token_id: swap.rhs.unwrap().token_id.clone(),
-            })?,
-        })]))
+        .add_attribute("method", "cancel_nonces")
+        .add_attribute("signer", info.sender)
+        .add_attribute("cancelled", nonces.len().to_string()))
+}
+
+pub fn increment_min_nonce(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    min_nonce: Uint64,
+) -> Result<Response, ContractError> {
+    let current = MIN_NONCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if min_nonce.u64() <= current {
+        return Err(ContractError::MinNonceNotIncreased { min_nonce: current });
+    }
+    MIN_NONCES.save(deps.storage, &info.sender, &min_nonce.u64())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "increment_min_nonce")
+        .add_attribute("signer", info.sender)
+        .add_attribute("min_nonce", min_nonce))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lock_htlc(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_id: String,
+    recipient: String,
+    hashlock: Binary,
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    ensure_not_shutdown(&deps)?;
+
+    let collection = validate_collection(&deps, collection)?;
+    let recipient = validate_account(&deps, recipient)?;
+    if hashlock.len() != 32 {
+        return Err(ContractError::InvalidHashlock {});
+    }
+    if matches!(expires, Expiration::Never {}) {
+        return Err(ContractError::HtlcTimelockRequired {});
+    }
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::HtlcExpired {});
+    }
+    ensure_collection_allowed(&deps, &collection)?;
+    ensure_not_escrowed(&deps, &collection, &token_id)?;
+    ensure_can_escrow(&deps, &env, &info.sender, &collection, &token_id)?;
+
+    let htlc_id = NEXT_HTLC_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_HTLC_ID.save(deps.storage, &(htlc_id + 1))?;
+    let htlc = Htlc {
+        depositor: info.sender,
+        recipient,
+        collection,
+        token_id,
+        hashlock,
+        expires,
+        status: HtlcStatus::Locked,
+    };
+    HTLCS.save(deps.storage, htlc_id.into(), &htlc)?;
+    ledger_deposit_nft(deps.storage, &htlc.collection, &htlc.token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "lock_htlc")
+        .add_attribute("htlc_id", htlc_id.to_string())
+        .add_event(HtlcEvent::Locked.build(htlc_id, &htlc, None))
+        .add_message(transfer_token(
+            &htlc.collection,
+            &htlc.token_id,
+            &env.contract.address,
+        )?))
+}
+
+pub fn claim_htlc(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    htlc_id: Uint64,
+    preimage: Binary,
+) -> Result<Response, ContractError> {
+    let mut htlc = load_htlc(&deps, htlc_id)?;
+    if htlc.status != HtlcStatus::Locked {
+        return Err(ContractError::HtlcNotLocked {
+            status: htlc.status,
+        });
+    }
+    if htlc.expires.is_expired(&env.block) {
+        return Err(ContractError::HtlcExpired {});
+    }
+    if Sha256::digest(&preimage).as_slice() != htlc.hashlock.as_slice() {
+        return Err(ContractError::InvalidPreimage {});
+    }
+    htlc.status = HtlcStatus::Claimed;
+    HTLCS.save(deps.storage, htlc_id.u64().into(), &htlc)?;
+    PREIMAGES.save(deps.storage, &htlc.hashlock, &preimage)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "claim_htlc")
+        .add_attribute("htlc_id", htlc_id.to_string())
+        .add_event(HtlcEvent::Claimed.build(htlc_id.u64(), &htlc, Some(&preimage)))
+        .add_messages(payout_nft(
+            deps.storage,
+            &htlc.collection,
+            &htlc.token_id,
+            &htlc.recipient,
+        )?))
+}
+
+pub fn refund_htlc(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    htlc_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut htlc = load_htlc(&deps, htlc_id)?;
+    if htlc.status != HtlcStatus::Locked {
+        return Err(ContractError::HtlcNotLocked {
+            status: htlc.status,
+        });
+    }
+    if !htlc.expires.is_expired(&env.block) {
+        return Err(ContractError::HtlcNotExpired {});
+    }
+    htlc.status = HtlcStatus::Refunded;
+    HTLCS.save(deps.storage, htlc_id.u64().into(), &htlc)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "refund_htlc")
+        .add_attribute("htlc_id", htlc_id.to_string())
+        .add_event(HtlcEvent::Refunded.build(htlc_id.u64(), &htlc, None))
+        .add_messages(payout_nft(
+            deps.storage,
+            &htlc.collection,
+            &htlc.token_id,
+            &htlc.depositor,
+        )?))
+}
+
+pub fn reject_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_reject(&swap, &info.sender, &env.block)?;
+    swap.status = swap.status.transition(SwapAction::Reject)?;
+    let event = SwapEvent::OfferRejected.build(swap_id.u64(), &swap);
+    let rhs = swap.rhs.take().unwrap();
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reject_offer")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(event)
+        .add_messages(refund_nft(deps.storage, swap.custody, &rhs)?))
+}
+
+pub fn withdraw_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_withdraw(&swap, &info.sender, &env.block)?;
+    swap.status = swap.status.transition(SwapAction::Withdraw)?;
+    let event = SwapEvent::OfferWithdrawn.build(swap_id.u64(), &swap);
+    let rhs = swap.rhs.take().unwrap();
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "withdraw_offer")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(event)
+        .add_messages(refund_nft(deps.storage, swap.custody, &rhs)?))
 }
 
 pub fn cancel_swap(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_cancel(&swap, &info.sender)?;
+    swap.status = swap.status.transition(SwapAction::Cancel)?;
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_swap")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Cancelled.build(swap_id.u64(), &swap))
+        .add_messages(refund_swap(deps.storage, &swap)?))
+}
+
+pub fn cancel_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_CANCEL_ALL_LIMIT)
+        .min(MAX_CANCEL_ALL_LIMIT) as usize;
+    let owner = info.sender;
+    // Every call starts over, so swaps listed or offered on since the previous call are not
+    // missed. Cancelled listings and withdrawn offers are skipped.
+    let pending = cancellable_swaps(deps.storage, &owner, &env.block, limit + 1)?;
+    let done = pending.len() <= limit;
+
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut listings = 0;
+    let mut offers = 0;
+    for (swap_id, mut swap, action) in pending.into_iter().take(limit) {
+        match action {
+            SwapAction::Cancel => listings += 1,
+            _ => offers += 1,
+        }
+        swap.status = swap.status.transition(action)?;
+        if action == SwapAction::Withdraw {
+            let event = SwapEvent::OfferWithdrawn.build(swap_id, &swap);
+            let rhs = swap.rhs.take().unwrap();
+            save_swap(deps.storage, swap_id, &swap)?;
+            messages.extend(refund_nft(deps.storage, swap.custody, &rhs)?);
+            events.push(event);
+        } else {
+            let event = match action {
+                SwapAction::Cancel => SwapEvent::Cancelled,
+                _ => SwapEvent::Expired,
+            };
+            save_swap(deps.storage, swap_id, &swap)?;
+            messages.extend(refund_swap(deps.storage, &swap)?);
+            events.push(event.build(swap_id, &swap));
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_all")
+        .add_attribute("listings", listings.to_string())
+        .add_attribute("offers", offers.to_string())
+        .add_attribute("done", done.to_string())
+        .add_events(events)
+        .add_messages(messages))
+}
+
+pub fn reclaim_expired_swap(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    swap_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut swap = load_swap(&deps, swap_id)?;
+    ensure_can_reclaim(&swap, &env.block)?;
+    swap.status = swap.status.transition(SwapAction::Expire)?;
+    save_swap(deps.storage, swap_id.u64(), &swap)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reclaim_expired_swap")
+        .add_attribute("swap_id", swap_id.to_string())
+        .add_event(SwapEvent::Expired.build(swap_id.u64(), &swap))
+        .add_messages(refund_swap(deps.storage, &swap)?))
+}
+
+pub fn shutdown(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.shutdown = true;
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "shutdown"))
+}
+
+pub fn refund_all(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    swap_id: String,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let swap = match SWAPS.load(deps.storage, swap_id.clone()) {
-        Ok(swap) => swap,
-        Err(_) => {
-            return Err(ContractError::SwapNotFound {});
+    if !CONFIG.load(deps.storage)?.shutdown {
+        return Err(ContractError::ContractNotShutdown {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_REFUND_LIMIT).min(MAX_REFUND_LIMIT) as usize;
+    let start = REFUND_PROGRESS
+        .may_load(deps.storage)?
+        .map(Bound::exclusive_int);
+    let page = swaps()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut refunded = 0;
+    for (key, mut swap) in page {
+        let swap_id = swap_id_from_key(&key)?;
+        REFUND_PROGRESS.save(deps.storage, &swap_id)?;
+        // Failed settlements keep their own recovery path, the NFTs are owed to the other party
+        if swap.status.is_terminal() || swap.status == SwapStatus::SettlementFailed {
+            continue;
         }
-    };
 
-    SWAPS.remove(deps.storage, swap_id.clone());
+        swap.status = swap.status.transition(SwapAction::Cancel)?;
+        save_swap(deps.storage, swap_id, &swap)?;
+        messages.extend(refund_swap(deps.storage, &swap)?);
+        events.push(SwapEvent::Cancelled.build(swap_id, &swap));
+        refunded += 1;
+    }
+
+    let start = REFUND_PROGRESS
+        .may_load(deps.storage)?
+        .map(Bound::exclusive_int);
+    let remaining = swaps()
+        .range(deps.storage, start, None, Order::Ascending)
+        .next()
+        .is_some();
 
     Ok(Response::new()
-        .add_attribute("method", "cancel_swap")
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: swap.lhs.collection.to_string(),
-            funds: vec![],
-            msg: to_binary(&TransferNft {
-                recipient: swap.lhs.owner.to_string(),
-                token_id: swap.lhs.token_id.clone(),
-            })?,
-        })]))
+        .add_attribute("method", "refund_all")
+        .add_attribute("refunded", refunded.to_string())
+        .add_attribute("done", (!remaining).to_string())
+        .add_events(events)
+        .add_messages(messages))
+}
+
+pub fn update_collection_mode(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    mode: CollectionMode,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.collection_mode = mode;
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "update_collection_mode"))
+}
+
+pub fn update_payout_mode(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    mode: PayoutMode,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.payout_mode = mode;
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "update_payout_mode"))
+}
+
+pub fn claim(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let claims = CLAIMS
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    if claims.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    let done = claims.len() <= limit;
+
+    let mut messages = vec![];
+    for (key, claim) in claims.into_iter().take(limit) {
+        let claim_id = swap_id_from_key(&key)?;
+        CLAIMS.remove(deps.storage, (&info.sender, claim_id.into()));
+        let Claimable::Nft {
+            collection,
+            token_id,
+        } = claim;
+        ledger_withdraw_nft(deps.storage, &collection, &token_id);
+        messages.push(transfer_token(&collection, &token_id, &info.sender)?);
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "claim")
+        .add_attribute("claimed", messages.len().to_string())
+        .add_attribute("done", done.to_string())
+        .add_messages(messages))
+}
+
+pub fn update_allowed_collections(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    update_collection_list(deps, ALLOWED_COLLECTIONS, add, remove)?;
+
+    Ok(Response::new().add_attribute("method", "update_allowed_collections"))
+}
+
+pub fn update_denied_collections(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    update_collection_list(deps, DENIED_COLLECTIONS, add, remove)?;
+
+    Ok(Response::new().add_attribute("method", "update_denied_collections"))
+}
+
+/// Runs each message as the sender. The chain reverts the whole transaction when one fails,
+/// so the batch is atomic.
+pub fn batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    if msgs.len() > MAX_BATCH_SIZE as usize {
+        return Err(ContractError::BatchTooLarge {
+            max: MAX_BATCH_SIZE,
+        });
+    }
+
+    let mut response = Response::new()
+        .add_attribute("method", "batch")
+        .add_attribute("count", msgs.len().to_string());
+    for msg in msgs {
+        if matches!(msg, ExecuteMsg::Batch { .. }) {
+            return Err(ContractError::NestedBatch {});
+        }
+        let res = execute(deps.branch(), env.clone(), info.clone(), msg)?;
+        response = response
+            .add_submessages(res.messages)
+            .add_attributes(res.attributes)
+            .add_events(res.events);
+    }
+    Ok(response)
+}
+
+fn update_collection_list(
+    deps: DepsMut,
+    list: Map<&Addr, Empty>,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<(), ContractError> {
+    for address in add {
+        let collection = validate_collection(&deps, address)?;
+        list.save(deps.storage, &collection, &Empty {})?;
+    }
+    for address in remove {
+        let collection = validate_collection(&deps, address)?;
+        list.remove(deps.storage, &collection);
+    }
+    Ok(())
+}
+
+fn validate_collection(deps: &DepsMut, address: String) -> Result<Addr, ContractError> {
+    deps.api
+        .addr_validate(&address)
+        .map_err(|_| ContractError::InvalidAddress { address })
+}
+
+fn validate_account(deps: &DepsMut, address: String) -> Result<Addr, ContractError> {
+    deps.api
+        .addr_validate(&address)
+        .map_err(|_| ContractError::InvalidAccountAddress { address })
+}
+
+fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<(), ContractError> {
+    if CONFIG.load(deps.storage)?.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn ensure_collection_allowed(deps: &DepsMut, collection: &Addr) -> Result<(), ContractError> {
+    let allowed = match CONFIG.load(deps.storage)?.collection_mode {
+        CollectionMode::Open => true,
+        CollectionMode::Allowlist => ALLOWED_COLLECTIONS.has(deps.storage, collection),
+        CollectionMode::Denylist => !DENIED_COLLECTIONS.has(deps.storage, collection),
+    };
+    if !allowed {
+        return Err(ContractError::CollectionNotAllowed {
+            collection: collection.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn ensure_not_shutdown(deps: &DepsMut) -> Result<(), ContractError> {
+    permissions::ensure_not_shutdown(&CONFIG.load(deps.storage)?)
+}
+
+/// An NFT can only be held by one swap at a time
+fn ensure_not_escrowed(
+    deps: &DepsMut,
+    collection: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    match escrowed_token(deps.storage, collection, token_id)? {
+        Some((swap_id, _)) => Err(ContractError::TokenAlreadyEscrowed { swap_id }),
+        None => Ok(()),
+    }
+}
+
+/// Consumes the nonce of a signed offer so the signature can't be executed again
+fn use_nonce(storage: &mut dyn Storage, signer: &Addr, nonce: u64) -> Result<(), ContractError> {
+    let min_nonce = MIN_NONCES.may_load(storage, signer)?.unwrap_or_default();
+    if nonce < min_nonce {
+        return Err(ContractError::NonceBelowMinimum { nonce, min_nonce });
+    }
+    if USED_NONCES.has(storage, (signer, nonce.into())) {
+        return Err(ContractError::NonceUsed { nonce });
+    }
+    USED_NONCES.save(storage, (signer, nonce.into()), &Empty {})?;
+    Ok(())
+}
+
+/// Up to `limit` swaps `CancelAll` acts on with the action to take, the owner's listings first
+/// and then their offers. Only unfinished swaps are read, so finished ones cost nothing.
+fn cancellable_swaps(
+    storage: &dyn Storage,
+    owner: &Addr,
+    block: &BlockInfo,
+    limit: usize,
+) -> StdResult<Vec<(u64, Swap, SwapAction)>> {
+    let swaps = swaps();
+    let indexes = [
+        (&swaps.idx.active_lhs_owner, SwapRole::Listing),
+        (&swaps.idx.active_rhs_owner, SwapRole::Offer),
+    ];
+    let mut pending = vec![];
+    for (index, role) in &indexes {
+        let page = index
+            .prefix(owner.as_bytes().to_vec())
+            .range(storage, None, None, Order::Ascending)
+            .filter_map(|item| match item {
+                Ok((key, swap)) => cancel_all_action(&swap, owner, *role, block).map(|action| {
+                    let swap_id = swap_id_from_key(&key)?;
+                    Ok((swap_id, swap, action))
+                }),
+                Err(err) => Some(Err(err)),
+            })
+            .take(limit - pending.len())
+            .collect::<StdResult<Vec<_>>>()?;
+        pending.extend(page);
+    }
+    Ok(pending)
+}
+
+fn cancel_all_action(
+    swap: &Swap,
+    owner: &Addr,
+    role: SwapRole,
+    block: &BlockInfo,
+) -> Option<SwapAction> {
+    match role {
+        SwapRole::Listing => ensure_can_cancel(swap, owner)
+            .ok()
+            .map(|_| SwapAction::Cancel),
+        // Offers on the owner's own listings go with the listing
+        SwapRole::Offer if swap.lhs.owner == *owner => None,
+        SwapRole::Offer => ensure_can_withdraw(swap, owner, block)
+            .map(|_| SwapAction::Withdraw)
+            .or_else(|_| ensure_can_reclaim(swap, block).map(|_| SwapAction::Expire))
+            .ok(),
+    }
+}
+
+fn load_htlc(deps: &DepsMut, htlc_id: Uint64) -> Result<Htlc, ContractError> {
+    HTLCS
+        .may_load(deps.storage, htlc_id.u64().into())?
+        .ok_or(ContractError::HtlcNotFound {})
+}
+
+fn load_swap(deps: &DepsMut, swap_id: Uint64) -> Result<Swap, ContractError> {
+    swaps()
+        .may_load(deps.storage, swap_id.u64().into())?
+        .ok_or(ContractError::SwapNotFound {})
+}
+
+/// Returns every escrowed NFT of the swap to its depositor
+fn refund_swap(storage: &mut dyn Storage, swap: &Swap) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = refund_nft(storage, swap.custody, &swap.lhs)?;
+    if let Some(rhs) = &swap.rhs {
+        messages.extend(refund_nft(storage, swap.custody, rhs)?);
+    }
+    Ok(messages)
+}
+
+/// Transfers the NFT back to its depositor, or credits it to their claims in
+/// `PayoutMode::Claim`. With approval custody it never left its owner.
+fn refund_nft(
+    storage: &mut dyn Storage,
+    custody: Custody,
+    side: &SwapSide,
+) -> StdResult<Vec<CosmosMsg>> {
+    if custody == Custody::Approval {
+        return Ok(vec![]);
+    }
+    payout_nft(storage, &side.collection, &side.token_id, &side.owner)
+}
+
+/// Sends a held NFT, or credits it in `PayoutMode::Claim`
+fn payout_nft(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    token_id: &str,
+    recipient: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    match CONFIG.load(storage)?.payout_mode {
+        PayoutMode::Push => {
+            ledger_withdraw_nft(storage, collection, token_id);
+            Ok(vec![transfer_token(collection, token_id, recipient)?])
+        }
+        PayoutMode::Claim => {
+            let claim = Claimable::Nft {
+                collection: collection.clone(),
+                token_id: token_id.to_string(),
+            };
+            credit_claim(storage, recipient, &claim)?;
+            Ok(vec![])
+        }
+    }
+}
+
+/// Moves the NFT into the contract, with approval custody it stays with its owner
+fn escrow_nft(
+    storage: &mut dyn Storage,
+    swap: &Swap,
+    side: &SwapSide,
+    contract: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    match swap.custody {
+        Custody::Escrow => {
+            ledger_deposit_nft(storage, &side.collection, &side.token_id)?;
+            Ok(vec![transfer_nft(side, contract)?])
+        }
+        Custody::Approval => Ok(vec![]),
+    }
+}
+
+/// Transfers each side's NFT to the other party, a failure is handled by `settlement_failed`.
+/// In `PayoutMode::Claim` escrowed NFTs are credited instead and nothing is sent. NFTs under
+/// approval custody are always transferred straight from their owners, without a reply, so
+/// either both transfers land or the whole settlement reverts.
+fn settlement_transfers(
+    storage: &mut dyn Storage,
+    swap_id: u64,
+    swap: &Swap,
+    roles: &[SwapRole],
+) -> StdResult<Vec<SubMsg>> {
+    let rhs = swap.rhs.as_ref().unwrap();
+    let transfers = roles.iter().map(|role| match role {
+        SwapRole::Listing => (*role, &swap.lhs, &rhs.owner),
+        SwapRole::Offer => (*role, rhs, &swap.lhs.owner),
+    });
+
+    if swap.custody == Custody::Escrow && CONFIG.load(storage)?.payout_mode == PayoutMode::Claim {
+        for (_, side, recipient) in transfers {
+            credit_claim(storage, recipient, &nft_claim(side))?;
+        }
+        return Ok(vec![]);
+    }
+    transfers
+        .map(|(role, side, recipient)| {
+            if swap.custody == Custody::Approval {
+                return Ok(SubMsg::new(transfer_nft(side, recipient)?));
+            }
+            ledger_withdraw_nft(storage, &side.collection, &side.token_id);
+            Ok(SubMsg::reply_on_error(
+                transfer_nft(side, recipient)?,
+                settlement_reply_id(swap_id, role),
+            ))
+        })
+        .collect()
+}
+
+fn nft_claim(side: &SwapSide) -> Claimable {
+    Claimable::Nft {
+        collection: side.collection.clone(),
+        token_id: side.token_id.clone(),
+    }
+}
+
+/// The lowest bit tells which side's transfer failed, the rest is the swap id
+fn settlement_reply_id(swap_id: u64, role: SwapRole) -> u64 {
+    match role {
+        SwapRole::Listing => swap_id << 1,
+        SwapRole::Offer => swap_id << 1 | 1,
+    }
+}
+
+fn parse_settlement_reply_id(id: u64) -> Result<(u64, SwapRole), ContractError> {
+    let role = match id & 1 {
+        0 => SwapRole::Listing,
+        _ => SwapRole::Offer,
+    };
+    match id >> 1 {
+        0 => Err(ContractError::UnknownReplyId { id }),
+        swap_id => Ok((swap_id, role)),
+    }
+}
+
+fn transfer_nft(side: &SwapSide, recipient: &Addr) -> StdResult<CosmosMsg> {
+    transfer_token(&side.collection, &side.token_id, recipient)
+}
+
+fn transfer_token(collection: &Addr, token_id: &str, recipient: &Addr) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: collection.to_string(),
+        funds: vec![],
+        msg: to_binary(&TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        })?,
+    }))
 }
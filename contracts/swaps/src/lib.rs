@@ -1,8 +1,14 @@
 pub mod contract;
 pub mod error;
+mod events;
+mod executions;
+mod migrations;
 pub mod msg;
-pub mod state;
+mod permissions;
+mod preflight;
 mod queries;
-mod executions;
+mod signatures;
+mod simulation;
+pub mod state;
 
 pub use crate::error::ContractError;
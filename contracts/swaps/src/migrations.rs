@@ -0,0 +1,229 @@
+use cosmwasm_std::{DepsMut, Empty, Env, Order, Response, StdError, StdResult};
+use cw2::{get_contract_version, set_contract_version};
+use cw721::Expiration;
+use cw_storage_plus::{Item, Map, U64Key};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::executions::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::msg::MigrateMsg;
+use crate::state::{
+    ledger_deposit_nft, record_settlement, swaps, CollectionMode, Config, Custody, PayoutMode,
+    Stats, Swap, SwapRole, SwapSide, SwapStatus, CONFIG, STATS, SWAPS_NAMESPACE,
+};
+
+type MigrationStep = fn(&mut DepsMut, &Env, &MigrateMsg) -> Result<(), ContractError>;
+
+/// Every step is tagged with the version that introduced it and runs only when migrating from
+/// an older version. Steps run in the order they are listed.
+const MIGRATIONS: &[(&str, MigrationStep)] = &[
+    ("0.2.0", add_admin_to_config),
+    ("0.2.0", rekey_swaps_by_number),
+    ("0.2.0", add_status_to_swaps),
+    ("0.2.0", index_swaps),
+    ("0.2.0", count_swaps),
+    ("0.2.0", record_escrow_ledger),
+];
+
+pub fn migrate_state(
+    mut deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+
+    let previous_version = parse_version(&stored.version)?;
+    let new_version = parse_version(CONTRACT_VERSION)?;
+    if previous_version > new_version {
+        return Err(ContractError::CannotDowngrade {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    for (version, step) in MIGRATIONS {
+        if parse_version(version)? > previous_version {
+            step(&mut deps, &env, &msg)?;
+        }
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("previous_version", stored.version)
+        .add_attribute("new_version", CONTRACT_VERSION))
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version)
+        .map_err(|_| StdError::generic_err(format!("Invalid contract version: {}", version)))
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConfigV0_1 {
+    next_swap_id: u32,
+}
+
+/// 0.1.0 stored only the swap counter
+fn add_admin_to_config(
+    deps: &mut DepsMut,
+    _env: &Env,
+    msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let legacy: Item<ConfigV0_1> = Item::new("config");
+    let config = legacy.load(deps.storage)?;
+
+    let admin = match &msg.admin {
+        Some(admin) => deps.api.addr_validate(admin)?,
+        None => return Err(ContractError::MigrationAdminRequired {}),
+    };
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            next_swap_id: config.next_swap_id.into(),
+            admin,
+            shutdown: false,
+            collection_mode: CollectionMode::Open,
+            payout_mode: PayoutMode::Push,
+        },
+    )?;
+    Ok(())
+}
+
+/// 0.1.0 keyed swaps by the decimal string of their id. Values are copied as raw bytes, so this
+/// step does not depend on the current shape of `Swap`.
+fn rekey_swaps_by_number(
+    deps: &mut DepsMut,
+    _env: &Env,
+    _msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let legacy: Map<&str, Empty> = Map::new(SWAPS_NAMESPACE);
+    let rekeyed: Map<U64Key, Empty> = Map::new(SWAPS_NAMESPACE);
+
+    let swap_ids = legacy
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| String::from_utf8(key).map_err(StdError::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // All legacy entries are removed before any new one is written, so the two key formats,
+    // which share a namespace, can never overwrite each other.
+    let mut swaps = vec![];
+    for swap_id in swap_ids {
+        let legacy_key = legacy.key(&swap_id);
+        let swap = deps.storage.get(&legacy_key).unwrap_or_default();
+        deps.storage.remove(&legacy_key);
+
+        match swap_id.parse::<u64>() {
+            Ok(id) if id.to_string() == swap_id => swaps.push((id, swap)),
+            _ => return Err(ContractError::InvalidSwapId { swap_id }),
+        }
+    }
+
+    for (id, swap) in swaps {
+        deps.storage.set(&rekeyed.key(id.into()), &swap);
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct SwapV0_1 {
+    lhs: SwapSide,
+    rhs: Option<SwapSide>,
+}
+
+/// 0.1.0 swaps had no status and never expired
+fn add_status_to_swaps(
+    deps: &mut DepsMut,
+    _env: &Env,
+    _msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let legacy: Map<U64Key, SwapV0_1> = Map::new(SWAPS_NAMESPACE);
+    let migrated: Map<U64Key, Swap> = Map::new(SWAPS_NAMESPACE);
+    let swaps = legacy
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (key, swap) in swaps {
+        let status = match swap.rhs {
+            Some(_) => SwapStatus::Offered,
+            None => SwapStatus::Open,
+        };
+        let swap = Swap {
+            lhs: swap.lhs,
+            rhs: swap.rhs,
+            status,
+            expires: Expiration::Never {},
+            failed_transfers: vec![],
+            custody: Custody::Escrow,
+        };
+        migrated.save(deps.storage, key.into(), &swap)?;
+    }
+    Ok(())
+}
+
+/// 0.1.0 had no secondary indexes over swaps
+fn index_swaps(deps: &mut DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    let all_swaps = swaps()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (key, swap) in all_swaps {
+        swaps().save(deps.storage, key.into(), &swap)?;
+    }
+    Ok(())
+}
+
+/// 0.1.0 kept no stats, they are counted once from the stored swaps
+fn count_swaps(deps: &mut DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    let all_swaps = swaps()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut stats = Stats::default();
+    for (_, swap) in all_swaps {
+        stats.add(&swap);
+        if swap.status == SwapStatus::Completed {
+            record_settlement(deps.storage, &swap)?;
+        }
+    }
+    STATS.save(deps.storage, &stats)?;
+    Ok(())
+}
+
+/// 0.1.0 kept no ledger, every NFT still held for a swap is recorded
+fn record_escrow_ledger(
+    deps: &mut DepsMut,
+    _env: &Env,
+    _msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let all_swaps = swaps()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (_, swap) in all_swaps {
+        if swap.custody == Custody::Approval {
+            continue;
+        }
+        let sides = [
+            (SwapRole::Listing, Some(&swap.lhs)),
+            (SwapRole::Offer, swap.rhs.as_ref()),
+        ];
+        for (role, side) in &sides {
+            match side {
+                Some(side) if swap.holds(*role) => {
+                    ledger_deposit_nft(deps.storage, &side.collection, &side.token_id)?
+                }
+                _ => (),
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,39 +1,471 @@
-use crate::state::SwapSide;
+use crate::state::{
+    Claimable, CollectionMode, Custody, HtlcStatus, PayoutMode, SwapAction, SwapRole, SwapSide,
+    SwapStatus,
+};
+use cosmwasm_std::{Addr, Attribute, Binary, Coin, Event, SubMsg, Uint64};
+use cw721::ContractInfoResponse;
+use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {}
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// Admin of the contract, required when migrating from a version that did not store one
+    pub admin: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     InitiateSwap {
         collection: String,
         token_id: String,
+        /// Never expires when omitted
+        expires: Option<Expiration>,
+        /// Escrow when omitted. Offers follow the custody of the swap.
+        custody: Option<Custody>,
     },
     SwapReply {
-        swap_id: String,
+        swap_id: Uint64,
         collection: String,
         token_id: String,
     },
     FinalizeSwap {
-        swap_id: String,
+        swap_id: Uint64,
+    },
+    /// Returns the offered NFT to its owner and reopens the swap. Lhs owner only.
+    RejectOffer {
+        swap_id: Uint64,
+    },
+    /// Returns the offered NFT to its owner and reopens the swap. Rhs owner only.
+    WithdrawOffer {
+        swap_id: Uint64,
+    },
+    /// Sends the NFTs of a failed settlement again. Either party may call it.
+    RetrySettlement {
+        swap_id: Uint64,
+    },
+    /// Returns both NFTs of a failed settlement to their depositors, allowed only when neither
+    /// was delivered. Either party may call it.
+    UnwindSettlement {
+        swap_id: Uint64,
+    },
+    /// Settles a swap the lister signed off-chain in a single transaction, the sender gives
+    /// `token_id` of the wanted collection. Both NFTs move straight from their owners' wallets,
+    /// so both must have approved this contract. `signature` is the 64 byte secp256k1 signature
+    /// of the sha256 hash of the JSON encoded `offer`, `pubkey` the lister's compressed key.
+    ExecuteSignedOffer {
+        offer: SignedOffer,
+        token_id: String,
+        signature: Binary,
+        pubkey: Binary,
+    },
+    /// Revokes signed offers of the sender by marking their nonces used
+    CancelNonces {
+        nonces: Vec<Uint64>,
+    },
+    /// Revokes every signed offer of the sender with a nonce below `min_nonce`, which must be
+    /// higher than the current minimum
+    IncrementMinNonce {
+        min_nonce: Uint64,
+    },
+    /// Escrows the NFT until `expires`. Whoever reveals the preimage of `hashlock`, a sha256
+    /// hash, releases it to `recipient`, so the counterparty on another chain can lock its leg
+    /// under the same hash.
+    LockHtlc {
+        collection: String,
+        token_id: String,
+        recipient: String,
+        hashlock: Binary,
+        expires: Expiration,
+    },
+    /// Releases the NFT to the recipient and publishes the preimage. Anyone may call it before
+    /// the HTLC expires.
+    ClaimHtlc {
+        htlc_id: Uint64,
+        preimage: Binary,
+    },
+    /// Returns the NFT of an expired HTLC to its depositor. Anyone may call it.
+    RefundHtlc {
+        htlc_id: Uint64,
+    },
+    /// Cancels the sender's listings, then withdraws their offers, up to `limit` swaps per call.
+    /// Expired offers are reclaimed instead. Call again until `done` is true, each call starts
+    /// over from the sender's unfinished swaps, so finished ones are never read.
+    CancelAll {
+        limit: Option<u32>,
     },
+    /// Lhs owner only
     CancelSwap {
-        swap_id: String,
+        swap_id: Uint64,
+    },
+    /// Returns the escrowed NFTs of an expired swap to their depositors. Anyone may call it.
+    ReclaimExpiredSwap {
+        swap_id: Uint64,
+    },
+    /// Puts the contract into wind-down mode. Admin only, cannot be undone.
+    Shutdown {},
+    /// Refunds up to `limit` escrowed swaps to their depositors. Anyone may call it once the
+    /// contract is shut down.
+    RefundAll {
+        limit: Option<u32>,
+    },
+    /// Admin only
+    UpdateCollectionMode {
+        mode: CollectionMode,
+    },
+    /// Admin only, applies to every payout from then on
+    UpdatePayoutMode {
+        mode: PayoutMode,
+    },
+    /// Withdraws up to `limit` of the sender's pending claims
+    Claim {
+        limit: Option<u32>,
+    },
+    /// Admin only
+    UpdateAllowedCollections {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Admin only
+    UpdateDeniedCollections {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Runs up to 30 messages in order as one transaction, if any of them fails none is
+    /// applied. The response combines their messages, attributes and events. Batches can't
+    /// be nested.
+    Batch {
+        msgs: Vec<ExecuteMsg>,
     },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetSwap { swap_id: String },
+    GetSwap {
+        swap_id: Uint64,
+    },
+    /// Swap along with the actions the viewer may take on it right now
+    SwapPermissions {
+        swap_id: Uint64,
+        viewer: String,
+    },
+    /// Runs an execute message as `sender` without saving anything
+    Simulate {
+        sender: String,
+        funds: Vec<Coin>,
+        msg: Box<ExecuteMsg>,
+    },
+    Stats {},
+    CollectionStats {
+        collection: String,
+    },
+    /// Swaps with the given ids, missing ones are marked as not found
+    GetSwaps {
+        ids: Vec<Uint64>,
+    },
+    /// Swap along with collection and NFT metadata queried from the collections
+    GetSwapDetails {
+        swap_id: Uint64,
+    },
+    GetConfig {},
+    /// Assets credited to the address in `PayoutMode::Claim`, oldest first
+    PendingClaims {
+        address: String,
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+    },
+    /// Minimum nonce of the signer and its used nonces from there on, in ascending order
+    Nonces {
+        address: String,
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+    },
+    /// Whether an offer signed by `address` with `nonce` can still be executed
+    NonceStatus {
+        address: String,
+        nonce: Uint64,
+    },
+    Htlc {
+        htlc_id: Uint64,
+    },
+    /// Preimage revealed by claiming an HTLC locked under `hashlock`
+    Preimage {
+        hashlock: Binary,
+    },
+    /// Compares the NFTs of `collection` the contract believes it holds with the tokens the
+    /// collection lists for it, ordered by token id
+    Reconcile {
+        collection: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllowedCollections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    DeniedCollections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Swaps ordered by id. `owner` matches the lhs owner, `collection` the listed collection.
+    /// The owner, collection or status index is ranged over, in that order of preference.
+    ListSwaps {
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+        status: Option<SwapStatus>,
+        owner: Option<String>,
+        collection: Option<String>,
+    },
+    /// Swaps waiting for an action of the given address, ordered by id
+    Inbox {
+        address: String,
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+    },
+    /// Swap currently holding the given NFT, if any. NFTs locked in an HTLC are held for no
+    /// swap and are not reported, see `Htlc`.
+    EscrowedToken {
+        collection: String,
+        token_id: String,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SwapResponse {
+    pub swap_id: Uint64,
     pub lhs: SwapSide,
     pub rhs: Option<SwapSide>,
+    pub status: SwapStatus,
+    pub expires: Expiration,
+    /// Sides whose settlement transfer failed
+    pub failed_transfers: Vec<SwapRole>,
+    pub custody: Custody,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HtlcResponse {
+    pub htlc_id: Uint64,
+    pub depositor: Addr,
+    pub recipient: Addr,
+    pub collection: Addr,
+    pub token_id: String,
+    pub hashlock: Binary,
+    pub expires: Expiration,
+    pub status: HtlcStatus,
+    /// Set once an HTLC under this hashlock was claimed
+    pub preimage: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreimageResponse {
+    pub preimage: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReconcileResponse {
+    /// Owned by the contract but missing from the ledger, e.g. sent to it directly
+    pub orphaned: Vec<String>,
+    /// In the ledger but no longer owned by the contract
+    pub missing: Vec<String>,
+    /// Tokens found in both
+    pub matched: u32,
+    /// Pass as `start_after` to fetch the next page, `None` when both sides are exhausted
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: Addr,
+    pub shutdown: bool,
+    pub collection_mode: CollectionMode,
+    pub payout_mode: PayoutMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionsResponse {
+    pub collections: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingClaim {
+    pub claim_id: Uint64,
+    pub claim: Claimable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingClaimsResponse {
+    pub claims: Vec<PendingClaim>,
+    pub next: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NoncesResponse {
+    pub min_nonce: Uint64,
+    /// Executed or cancelled nonces, lower ones are void anyway and not listed
+    pub used: Vec<Uint64>,
+    pub next: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NonceStatus {
+    Usable,
+    /// Executed or cancelled
+    Used,
+    /// Revoked by `IncrementMinNonce`
+    BelowMinimum,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NonceStatusResponse {
+    pub status: NonceStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListSwapsResponse {
+    pub swaps: Vec<SwapResponse>,
+    /// Pass as `start_after` to fetch the next page, `None` when there are no more swaps
+    pub next: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboxEntry {
+    pub swap: SwapResponse,
+    /// Actions the address can take on the swap right now
+    pub actions: Vec<SwapAction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboxResponse {
+    pub entries: Vec<InboxEntry>,
+    pub next: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenEscrow {
+    pub swap_id: Uint64,
+    pub role: SwapRole,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowedTokenResponse {
+    pub escrow: Option<TokenEscrow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapDetailsResponse {
+    pub swap: SwapResponse,
+    pub lhs: NftDetails,
+    pub rhs: Option<NftDetails>,
+}
+
+/// Metadata of an escrowed NFT, parts the collection failed to return are left empty
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftDetails {
+    pub collection: Option<ContractInfoResponse>,
+    pub nft: Option<NftMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftMetadata {
+    pub token_uri: Option<String>,
+    /// JSON encoded extension, as returned by the collection. It can be any JSON, so it is
+    /// left to clients to parse.
+    pub extension: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapLookup {
+    pub swap_id: Uint64,
+    /// None when no swap has this id
+    pub swap: Option<SwapResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetSwapsResponse {
+    /// One entry per requested id, in the requested order
+    pub swaps: Vec<SwapLookup>,
+}
+
+/// Swap terms signed by the lister, see `ExecuteMsg::ExecuteSignedOffer`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedOffer {
+    /// Signer of the offer and owner of the given NFT
+    pub lister: String,
+    pub give: OfferedNft,
+    pub want: WantedNft,
+    pub expires: Expiration,
+    /// Single use per lister, see `CancelNonces` and `IncrementMinNonce` for revocation
+    pub nonce: Uint64,
+    /// Swap contract the offer is meant for, so it can't be executed on another deployment
+    pub contract: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferedNft {
+    pub collection: String,
+    pub token_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WantedNft {
+    pub collection: String,
+    /// Any token of the collection when omitted
+    pub token_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Permission {
+    pub allowed: bool,
+    /// Error the action would fail with, when it is not allowed
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapPermissionsResponse {
+    pub swap: SwapResponse,
+    pub cancel: Permission,
+    pub accept: Permission,
+    pub reject: Permission,
+    pub withdraw: Permission,
+    pub reclaim: Permission,
+    pub respond: Permission,
+    pub retry: Permission,
+    pub unwind: Permission,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateResponse {
+    /// Error the message would fail with, nothing else is set then
+    pub error: Option<String>,
+    pub messages: Vec<SubMsg>,
+    pub attributes: Vec<Attribute>,
+    pub events: Vec<Event>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    /// Swaps by status
+    pub open: u64,
+    pub offered: u64,
+    pub completed: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    pub settlement_failed: u64,
+    pub broken: u64,
+    /// NFTs held for unfinished swaps, pending claims and NFTs locked in HTLCs are not included
+    pub escrowed_nfts: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionStatsResponse {
+    pub collection: Addr,
+    /// Completed swaps involving the collection
+    pub settled: u64,
 }
@@ -0,0 +1,120 @@
+use cosmwasm_std::{Addr, BlockInfo, MessageInfo};
+
+use crate::error::ContractError;
+use crate::state::{Config, Swap, SwapAction, SwapRole};
+
+// Authorization rules for actions on a swap, shared by executions and queries.
+// Checks run in the same order everywhere: sender, expiration, then status.
+
+/// Only the lhs owner can cancel, even after expiration
+pub fn ensure_can_cancel(swap: &Swap, sender: &Addr) -> Result<(), ContractError> {
+    ensure_lhs_owner(swap, sender)?;
+    ensure_transition(swap, SwapAction::Cancel)
+}
+
+/// Only the lhs owner can accept an offer
+pub fn ensure_can_accept(
+    swap: &Swap,
+    sender: &Addr,
+    block: &BlockInfo,
+) -> Result<(), ContractError> {
+    ensure_lhs_owner(swap, sender)?;
+    ensure_not_expired(swap, block)?;
+    ensure_transition(swap, SwapAction::Accept)
+}
+
+/// Only the lhs owner can reject an offer
+pub fn ensure_can_reject(
+    swap: &Swap,
+    sender: &Addr,
+    block: &BlockInfo,
+) -> Result<(), ContractError> {
+    ensure_lhs_owner(swap, sender)?;
+    ensure_not_expired(swap, block)?;
+    ensure_transition(swap, SwapAction::Reject)
+}
+
+/// Only the rhs owner can withdraw their offer
+pub fn ensure_can_withdraw(
+    swap: &Swap,
+    sender: &Addr,
+    block: &BlockInfo,
+) -> Result<(), ContractError> {
+    match &swap.rhs {
+        Some(rhs) if rhs.owner == *sender => (),
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+    ensure_not_expired(swap, block)?;
+    ensure_transition(swap, SwapAction::Withdraw)
+}
+
+/// Anyone can respond to an open swap, as long as the contract is not shut down
+pub fn ensure_can_respond(swap: &Swap, block: &BlockInfo) -> Result<(), ContractError> {
+    ensure_not_expired(swap, block)?;
+    ensure_transition(swap, SwapAction::Respond)
+}
+
+/// Anyone can return the NFTs of an expired swap to their owners
+pub fn ensure_can_reclaim(swap: &Swap, block: &BlockInfo) -> Result<(), ContractError> {
+    if !swap.is_expired(block) {
+        return Err(ContractError::SwapNotExpired {});
+    }
+    ensure_transition(swap, SwapAction::Expire)
+}
+
+/// Either party can send the NFTs of a failed settlement again
+pub fn ensure_can_retry(swap: &Swap, sender: &Addr) -> Result<(), ContractError> {
+    ensure_party(swap, sender)?;
+    ensure_transition(swap, SwapAction::Retry)
+}
+
+/// Either party can take their NFT back, but only when neither side was delivered
+pub fn ensure_can_unwind(swap: &Swap, sender: &Addr) -> Result<(), ContractError> {
+    ensure_party(swap, sender)?;
+    ensure_transition(swap, SwapAction::Unwind)?;
+    if !swap.holds(SwapRole::Listing) || !swap.holds(SwapRole::Offer) {
+        return Err(ContractError::SettlementPartiallyDelivered {});
+    }
+    Ok(())
+}
+
+/// No message takes funds, any sent along would be stuck in the contract
+pub fn ensure_nonpayable(info: &MessageInfo) -> Result<(), ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::NonPayable {});
+    }
+    Ok(())
+}
+
+/// No new swaps or offers once the contract is shut down
+pub fn ensure_not_shutdown(config: &Config) -> Result<(), ContractError> {
+    if config.shutdown {
+        return Err(ContractError::ContractShutdown {});
+    }
+    Ok(())
+}
+
+fn ensure_lhs_owner(swap: &Swap, sender: &Addr) -> Result<(), ContractError> {
+    if swap.lhs.owner != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn ensure_party(swap: &Swap, sender: &Addr) -> Result<(), ContractError> {
+    match &swap.rhs {
+        Some(rhs) if rhs.owner == *sender => Ok(()),
+        _ => ensure_lhs_owner(swap, sender),
+    }
+}
+
+fn ensure_not_expired(swap: &Swap, block: &BlockInfo) -> Result<(), ContractError> {
+    if swap.is_expired(block) {
+        return Err(ContractError::SwapExpired {});
+    }
+    Ok(())
+}
+
+fn ensure_transition(swap: &Swap, action: SwapAction) -> Result<(), ContractError> {
+    swap.status.transition(action).map(|_| ())
+}
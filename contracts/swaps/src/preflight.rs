@@ -0,0 +1,127 @@
+use cosmwasm_std::{
+    from_binary, to_binary, to_vec, Addr, Binary, ContractResult, DepsMut, Empty, Env,
+    QueryRequest, SystemResult, WasmQuery,
+};
+use cw721::{ApprovedForAllResponse, Cw721QueryMsg, NumTokensResponse, OwnerOfResponse};
+
+use crate::error::ContractError;
+
+/// cw721-base never returns more operators per page
+const OPERATORS_PAGE: u32 = 30;
+
+/// Checks with the collection that `owner` holds the NFT and that this contract may transfer
+/// it, so a bad token fails with a specific error before the swap is saved
+pub fn ensure_can_escrow(
+    deps: &DepsMut,
+    env: &Env,
+    owner: &Addr,
+    collection: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let owner_of = Cw721QueryMsg::OwnerOf {
+        token_id: token_id.to_string(),
+        include_expired: Some(false),
+    };
+    let owner_of: OwnerOfResponse = match query_cw721(deps, collection, &owner_of) {
+        Cw721Answer::Ok(response) => parse(collection, &response)?,
+        Cw721Answer::Rejected if is_cw721(deps, collection) => {
+            return Err(ContractError::TokenNotFound {
+                collection: collection.to_string(),
+                token_id: token_id.to_string(),
+            })
+        }
+        _ => return Err(not_cw721(collection)),
+    };
+    if owner_of.owner != owner.as_str() {
+        return Err(ContractError::NotTokenOwner {
+            collection: collection.to_string(),
+            token_id: token_id.to_string(),
+        });
+    }
+
+    let contract = env.contract.address.as_str();
+    let approved = |approval: &cw721::Approval| {
+        approval.spender == contract && !approval.expires.is_expired(&env.block)
+    };
+    if owner_of.approvals.iter().any(approved) {
+        return Ok(());
+    }
+
+    let mut start_after = None;
+    loop {
+        let approved_for_all = Cw721QueryMsg::ApprovedForAll {
+            owner: owner.to_string(),
+            include_expired: Some(false),
+            start_after: start_after.take(),
+            limit: Some(OPERATORS_PAGE),
+        };
+        let operators: ApprovedForAllResponse =
+            match query_cw721(deps, collection, &approved_for_all) {
+                Cw721Answer::Ok(response) => parse(collection, &response)?,
+                _ => return Err(not_cw721(collection)),
+            };
+        if operators.operators.iter().any(approved) {
+            return Ok(());
+        }
+        match operators.operators.last() {
+            Some(last) if operators.operators.len() == OPERATORS_PAGE as usize => {
+                start_after = Some(last.spender.clone())
+            }
+            _ => break,
+        }
+    }
+
+    Err(ContractError::ContractNotApproved {
+        collection: collection.to_string(),
+        token_id: token_id.to_string(),
+    })
+}
+
+enum Cw721Answer {
+    Ok(Binary),
+    /// The contract exists but returned an error
+    Rejected,
+    /// No contract answered
+    Unreachable,
+}
+
+fn query_cw721(deps: &DepsMut, collection: &Addr, msg: &Cw721QueryMsg) -> Cw721Answer {
+    let request: QueryRequest<Empty> = WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: match to_binary(msg) {
+            Ok(msg) => msg,
+            Err(_) => return Cw721Answer::Unreachable,
+        },
+    }
+    .into();
+    let request = match to_vec(&request) {
+        Ok(request) => request,
+        Err(_) => return Cw721Answer::Unreachable,
+    };
+    match deps.querier.raw_query(&request) {
+        SystemResult::Ok(ContractResult::Ok(response)) => Cw721Answer::Ok(response),
+        SystemResult::Ok(ContractResult::Err(_)) => Cw721Answer::Rejected,
+        SystemResult::Err(_) => Cw721Answer::Unreachable,
+    }
+}
+
+/// A cw721 contract answers `NumTokens` whatever tokens it holds
+fn is_cw721(deps: &DepsMut, collection: &Addr) -> bool {
+    match query_cw721(deps, collection, &Cw721QueryMsg::NumTokens {}) {
+        Cw721Answer::Ok(response) => from_binary::<NumTokensResponse>(&response).is_ok(),
+        _ => false,
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(
+    collection: &Addr,
+    response: &Binary,
+) -> Result<T, ContractError> {
+    from_binary(response).map_err(|_| not_cw721(collection))
+}
+
+fn not_cw721(collection: &Addr) -> ContractError {
+    ContractError::NotCw721Contract {
+        collection: collection.to_string(),
+    }
+}
@@ -1,9 +1,36 @@
-use crate::msg::SwapResponse;
-use crate::state::SWAPS;
-use cosmwasm_std::{Deps, StdError, StdResult};
+use std::collections::BTreeMap;
 
-pub fn get_swap(deps: Deps, swap_id: String) -> StdResult<SwapResponse> {
-    let swap = match SWAPS.load(deps.storage, swap_id) {
+use crate::error::ContractError;
+use crate::msg::{
+    CollectionStatsResponse, CollectionsResponse, ConfigResponse, EscrowedTokenResponse,
+    GetSwapsResponse, HtlcResponse, InboxEntry, InboxResponse, ListSwapsResponse, NftDetails,
+    NftMetadata, NonceStatus, NonceStatusResponse, NoncesResponse, PendingClaim,
+    PendingClaimsResponse, Permission, PreimageResponse, ReconcileResponse, StatsResponse,
+    SwapDetailsResponse, SwapLookup, SwapPermissionsResponse, SwapResponse, TokenEscrow,
+};
+use crate::permissions::{
+    ensure_can_accept, ensure_can_cancel, ensure_can_reclaim, ensure_can_reject,
+    ensure_can_respond, ensure_can_retry, ensure_can_unwind, ensure_can_withdraw,
+    ensure_not_shutdown,
+};
+use crate::state::{
+    escrowed_token, swap_id_from_key, swaps, Swap, SwapAction, SwapSide, SwapStatus,
+    ALLOWED_COLLECTIONS, CLAIMS, COLLECTION_STATS, CONFIG, DENIED_COLLECTIONS, HTLCS, LEDGER_NFTS,
+    MIN_NONCES, PREIMAGES, STATS, USED_NONCES,
+};
+use cosmwasm_std::{
+    to_binary, to_vec, Addr, Binary, BlockInfo, ContractResult, Deps, Empty, Env, Order,
+    QueryRequest, StdError, StdResult, SystemResult, Uint64, WasmQuery,
+};
+use cw721::{ContractInfoResponse, Cw721QueryMsg, NftInfoResponse, TokensResponse};
+use cw_storage_plus::{Bound, Map};
+use serde_json::value::RawValue;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn get_swap(deps: Deps, swap_id: Uint64) -> StdResult<SwapResponse> {
+    let swap = match swaps().load(deps.storage, swap_id.u64().into()) {
         Ok(swap) => swap,
         Err(_) => {
             return Err(StdError::NotFound {
@@ -12,8 +39,530 @@ pub fn get_swap(deps: Deps, swap_id: String) -> StdResult<SwapResponse> {
         }
     };
 
-    Ok(SwapResponse {
+    Ok(swap_response(swap_id.u64(), swap))
+}
+
+pub fn get_swaps(deps: Deps, ids: Vec<Uint64>) -> StdResult<GetSwapsResponse> {
+    if ids.len() > MAX_LIMIT as usize {
+        return Err(StdError::generic_err(format!(
+            "Too many ids, at most {} swaps can be fetched at once",
+            MAX_LIMIT
+        )));
+    }
+
+    let swaps = ids
+        .into_iter()
+        .map(|swap_id| {
+            let swap = swaps()
+                .may_load(deps.storage, swap_id.u64().into())?
+                .map(|swap| swap_response(swap_id.u64(), swap));
+            Ok(SwapLookup { swap_id, swap })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GetSwapsResponse { swaps })
+}
+
+pub fn get_swap_details(deps: Deps, swap_id: Uint64) -> StdResult<SwapDetailsResponse> {
+    let swap = get_swap(deps, swap_id)?;
+    let lhs = nft_details(deps, &swap.lhs);
+    let rhs = swap.rhs.as_ref().map(|rhs| nft_details(deps, rhs));
+
+    Ok(SwapDetailsResponse { swap, lhs, rhs })
+}
+
+fn nft_details(deps: Deps, side: &SwapSide) -> NftDetails {
+    let collection = deps
+        .querier
+        .query_wasm_smart::<ContractInfoResponse>(&side.collection, &Cw721QueryMsg::ContractInfo {})
+        .ok();
+    let nft_info = Cw721QueryMsg::NftInfo {
+        token_id: side.token_id.clone(),
+    };
+    let nft = query_wasm_raw_response(deps, &side.collection, &nft_info)
+        .and_then(|response| {
+            serde_json::from_slice::<NftInfoResponse<Option<Box<RawValue>>>>(&response).ok()
+        })
+        .map(|info| NftMetadata {
+            token_uri: info.token_uri,
+            extension: info
+                .extension
+                .map(|extension| Binary::from(extension.get().as_bytes())),
+        });
+
+    NftDetails { collection, nft }
+}
+
+/// Smart query returning the response bytes, or None if the contract failed to answer
+fn query_wasm_raw_response(deps: Deps, contract: &Addr, msg: &Cw721QueryMsg) -> Option<Binary> {
+    let request: QueryRequest<Empty> = WasmQuery::Smart {
+        contract_addr: contract.to_string(),
+        msg: to_binary(msg).ok()?,
+    }
+    .into();
+    match deps.querier.raw_query(&to_vec(&request).ok()?) {
+        SystemResult::Ok(ContractResult::Ok(response)) => Some(response),
+        _ => None,
+    }
+}
+
+pub fn list_swaps(
+    deps: Deps,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+    status: Option<SwapStatus>,
+    owner: Option<String>,
+    collection: Option<String>,
+) -> StdResult<ListSwapsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|swap_id| Bound::exclusive_int(swap_id.u64()));
+    let owner = owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?;
+    let collection = collection
+        .map(|collection| deps.api.addr_validate(&collection))
+        .transpose()?;
+
+    // The most selective index narrows the range, remaining filters are applied on top of it
+    let swaps = swaps();
+    let range = match (&owner, &collection, &status) {
+        (Some(owner), _, _) => swaps.idx.lhs_owner.prefix(owner.as_bytes().to_vec()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, Some(collection), _) => swaps
+            .idx
+            .collection
+            .prefix(collection.as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending),
+        (None, None, Some(status)) => swaps
+            .idx
+            .status
+            .prefix(status.as_str().as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending),
+        (None, None, None) => swaps.range(deps.storage, start, None, Order::Ascending),
+    };
+
+    let page = range
+        .filter(|item| match item {
+            Ok((_, swap)) => matches_filters(swap, &status, &collection),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (key, swap) = item?;
+            Ok(swap_response(swap_id_from_key(&key)?, swap))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = match page.last() {
+        Some(last) if page.len() == limit => Some(last.swap_id),
+        _ => None,
+    };
+
+    Ok(ListSwapsResponse { swaps: page, next })
+}
+
+pub fn inbox(
+    deps: Deps,
+    env: Env,
+    address: String,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+) -> StdResult<InboxResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let address = deps.api.addr_validate(&address)?;
+    let swaps = swaps();
+
+    // Finished swaps await nothing, so only the unfinished ones are read. Both sides are read
+    // up to the limit and merged, so the page stays ordered by id.
+    let mut entries = BTreeMap::new();
+    for index in &[&swaps.idx.active_lhs_owner, &swaps.idx.active_rhs_owner] {
+        let start = start_after.map(|swap_id| Bound::exclusive_int(swap_id.u64()));
+        let page = index
+            .prefix(address.as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending)
+            .map(|item| {
+                let (key, swap) = item?;
+                let actions = inbox_actions(&swap, &address, &env.block);
+                Ok((swap_id_from_key(&key)?, swap, actions))
+            })
+            .filter(|item: &StdResult<_>| match item {
+                Ok((_, _, actions)) => !actions.is_empty(),
+                Err(_) => true,
+            })
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (swap_id, swap, actions) in page {
+            let swap = swap_response(swap_id, swap);
+            entries.insert(swap_id, InboxEntry { swap, actions });
+        }
+    }
+
+    let entries: Vec<InboxEntry> = entries
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .take(limit)
+        .collect();
+    let next = match entries.last() {
+        Some(last) if entries.len() == limit => Some(last.swap.swap_id),
+        _ => None,
+    };
+
+    Ok(InboxResponse { entries, next })
+}
+
+/// Actions waiting for `address`: offers on its listings, and any of its expired swaps.
+/// Reclaiming is open to anyone but only counts for parties.
+fn inbox_actions(swap: &Swap, address: &Addr, block: &BlockInfo) -> Vec<SwapAction> {
+    let mut actions = vec![];
+    if ensure_can_accept(swap, address, block).is_ok() {
+        actions.push(SwapAction::Accept);
+    }
+    if ensure_can_reject(swap, address, block).is_ok() {
+        actions.push(SwapAction::Reject);
+    }
+    if ensure_can_reclaim(swap, block).is_ok() {
+        actions.push(SwapAction::Expire);
+    }
+    if ensure_can_retry(swap, address).is_ok() {
+        actions.push(SwapAction::Retry);
+    }
+    if ensure_can_unwind(swap, address).is_ok() {
+        actions.push(SwapAction::Unwind);
+    }
+    actions
+}
+
+/// Swap currently holding the NFT, if any
+pub fn get_escrowed_token(
+    deps: Deps,
+    collection: String,
+    token_id: String,
+) -> StdResult<EscrowedTokenResponse> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let escrow =
+        escrowed_token(deps.storage, &collection, &token_id)?.map(|(swap_id, role)| TokenEscrow {
+            swap_id: swap_id.into(),
+            role,
+        });
+
+    Ok(EscrowedTokenResponse { escrow })
+}
+
+pub fn swap_permissions(
+    deps: Deps,
+    env: Env,
+    swap_id: Uint64,
+    viewer: String,
+) -> StdResult<SwapPermissionsResponse> {
+    let viewer = deps.api.addr_validate(&viewer)?;
+    let config = CONFIG.load(deps.storage)?;
+    let swap = match swaps().load(deps.storage, swap_id.u64().into()) {
+        Ok(swap) => swap,
+        Err(_) => {
+            return Err(StdError::NotFound {
+                kind: String::from("Swap"),
+            })
+        }
+    };
+    let block = &env.block;
+
+    Ok(SwapPermissionsResponse {
+        cancel: permission(ensure_can_cancel(&swap, &viewer)),
+        accept: permission(ensure_can_accept(&swap, &viewer, block)),
+        reject: permission(ensure_can_reject(&swap, &viewer, block)),
+        withdraw: permission(ensure_can_withdraw(&swap, &viewer, block)),
+        reclaim: permission(ensure_can_reclaim(&swap, block)),
+        respond: permission(
+            ensure_not_shutdown(&config).and_then(|_| ensure_can_respond(&swap, block)),
+        ),
+        retry: permission(ensure_can_retry(&swap, &viewer)),
+        unwind: permission(ensure_can_unwind(&swap, &viewer)),
+        swap: swap_response(swap_id.u64(), swap),
+    })
+}
+
+fn permission(check: Result<(), ContractError>) -> Permission {
+    match check {
+        Ok(()) => Permission {
+            allowed: true,
+            reason: None,
+        },
+        Err(err) => Permission {
+            allowed: false,
+            reason: Some(err.to_string()),
+        },
+    }
+}
+
+fn matches_filters(swap: &Swap, status: &Option<SwapStatus>, collection: &Option<Addr>) -> bool {
+    if let Some(status) = status {
+        if swap.status != *status {
+            return false;
+        }
+    }
+    if let Some(collection) = collection {
+        if swap.lhs.collection != *collection {
+            return false;
+        }
+    }
+    true
+}
+
+fn swap_response(swap_id: u64, swap: Swap) -> SwapResponse {
+    SwapResponse {
+        swap_id: swap_id.into(),
         lhs: swap.lhs,
-        rhs: swap.rhs
+        rhs: swap.rhs,
+        status: swap.status,
+        expires: swap.expires,
+        failed_transfers: swap.failed_transfers,
+        custody: swap.custody,
+    }
+}
+
+pub fn get_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let stats = STATS.load(deps.storage)?;
+
+    Ok(StatsResponse {
+        open: stats.open,
+        offered: stats.offered,
+        completed: stats.completed,
+        cancelled: stats.cancelled,
+        expired: stats.expired,
+        settlement_failed: stats.settlement_failed,
+        broken: stats.broken,
+        escrowed_nfts: stats.escrowed_nfts,
+    })
+}
+
+pub fn get_collection_stats(deps: Deps, collection: String) -> StdResult<CollectionStatsResponse> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let stats = COLLECTION_STATS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+
+    Ok(CollectionStatsResponse {
+        collection,
+        settled: stats.settled,
+    })
+}
+
+pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        admin: config.admin,
+        shutdown: config.shutdown,
+        collection_mode: config.collection_mode,
+        payout_mode: config.payout_mode,
+    })
+}
+
+pub fn pending_claims(
+    deps: Deps,
+    address: String,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+) -> StdResult<PendingClaimsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|claim_id| Bound::exclusive_int(claim_id.u64()));
+
+    let claims = CLAIMS
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, claim) = item?;
+            Ok(PendingClaim {
+                claim_id: swap_id_from_key(&key)?.into(),
+                claim,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = match claims.last() {
+        Some(last) if claims.len() == limit => Some(last.claim_id),
+        _ => None,
+    };
+    Ok(PendingClaimsResponse { claims, next })
+}
+
+pub fn nonces(
+    deps: Deps,
+    address: String,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+) -> StdResult<NoncesResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min_nonce = MIN_NONCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let start = match start_after {
+        Some(nonce) if nonce.u64() >= min_nonce => Bound::exclusive_int(nonce.u64()),
+        _ => Bound::inclusive_int(min_nonce),
+    };
+
+    let used = USED_NONCES
+        .prefix(&address)
+        .keys(deps.storage, Some(start), None, Order::Ascending)
+        .take(limit)
+        .map(|key| Ok(swap_id_from_key(&key)?.into()))
+        .collect::<StdResult<Vec<Uint64>>>()?;
+
+    let next = match used.last() {
+        Some(last) if used.len() == limit => Some(*last),
+        _ => None,
+    };
+    Ok(NoncesResponse {
+        min_nonce: min_nonce.into(),
+        used,
+        next,
+    })
+}
+
+pub fn nonce_status(deps: Deps, address: String, nonce: Uint64) -> StdResult<NonceStatusResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let min_nonce = MIN_NONCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    let status = if nonce.u64() < min_nonce {
+        NonceStatus::BelowMinimum
+    } else if USED_NONCES.has(deps.storage, (&address, nonce.u64().into())) {
+        NonceStatus::Used
+    } else {
+        NonceStatus::Usable
+    };
+    Ok(NonceStatusResponse { status })
+}
+
+pub fn get_htlc(deps: Deps, htlc_id: Uint64) -> StdResult<HtlcResponse> {
+    let htlc = match HTLCS.may_load(deps.storage, htlc_id.u64().into())? {
+        Some(htlc) => htlc,
+        None => {
+            return Err(StdError::NotFound {
+                kind: String::from("Htlc"),
+            })
+        }
+    };
+    let preimage = PREIMAGES.may_load(deps.storage, &htlc.hashlock)?;
+
+    Ok(HtlcResponse {
+        htlc_id,
+        depositor: htlc.depositor,
+        recipient: htlc.recipient,
+        collection: htlc.collection,
+        token_id: htlc.token_id,
+        hashlock: htlc.hashlock,
+        expires: htlc.expires,
+        status: htlc.status,
+        preimage,
+    })
+}
+
+pub fn get_preimage(deps: Deps, hashlock: Binary) -> StdResult<PreimageResponse> {
+    Ok(PreimageResponse {
+        preimage: PREIMAGES.may_load(deps.storage, &hashlock)?,
     })
 }
+
+/// Merges a page of the ledger with a page of the collection's tokens. Both are ordered by token
+/// id, and each side returns a full page or is exhausted, so the first `limit` ids of the merge
+/// are complete.
+pub fn reconcile(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReconcileResponse> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let tokens = Cw721QueryMsg::Tokens {
+        owner: env.contract.address.to_string(),
+        start_after: start_after.clone(),
+        limit: Some(limit as u32),
+    };
+    let held: TokensResponse = deps.querier.query_wasm_smart(&collection, &tokens)?;
+    let recorded = LEDGER_NFTS
+        .prefix(&collection)
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|key| String::from_utf8(key).map_err(StdError::from))
+        .collect::<StdResult<Vec<_>>>()?;
+    let full_page = held.tokens.len() == limit || recorded.len() == limit;
+
+    let mut merged: BTreeMap<String, (bool, bool)> = BTreeMap::new();
+    for token_id in held.tokens {
+        merged.entry(token_id).or_default().0 = true;
+    }
+    for token_id in recorded {
+        merged.entry(token_id).or_default().1 = true;
+    }
+    let more = full_page || merged.len() > limit;
+
+    let mut response = ReconcileResponse {
+        orphaned: vec![],
+        missing: vec![],
+        matched: 0,
+        next: None,
+    };
+    for (token_id, (held, recorded)) in merged.into_iter().take(limit) {
+        match (held, recorded) {
+            (true, true) => response.matched += 1,
+            (true, false) => response.orphaned.push(token_id.clone()),
+            _ => response.missing.push(token_id.clone()),
+        }
+        if more {
+            response.next = Some(token_id);
+        }
+    }
+    Ok(response)
+}
+
+pub fn allowed_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    list_collections(deps, ALLOWED_COLLECTIONS, start_after, limit)
+}
+
+pub fn denied_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    list_collections(deps, DENIED_COLLECTIONS, start_after, limit)
+}
+
+fn list_collections(
+    deps: Deps,
+    list: Map<&Addr, Empty>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let collections = list
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| String::from_utf8(key).map(Addr::unchecked))
+        .collect::<Result<_, _>>()?;
+
+    Ok(CollectionsResponse { collections })
+}
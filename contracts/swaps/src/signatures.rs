@@ -0,0 +1,36 @@
+use bech32::FromBase32;
+use cosmwasm_std::{to_vec, Api};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::SignedOffer;
+
+/// Checks that `pubkey` belongs to the lister and that it signed the offer
+pub fn verify_signed_offer(
+    api: &dyn Api,
+    offer: &SignedOffer,
+    signature: &[u8],
+    pubkey: &[u8],
+) -> Result<(), ContractError> {
+    if !is_signer(&offer.lister, pubkey) {
+        return Err(ContractError::SignerMismatch {});
+    }
+    let hash = Sha256::digest(&to_vec(offer)?);
+    match api.secp256k1_verify(&hash, signature, pubkey) {
+        Ok(true) => Ok(()),
+        _ => Err(ContractError::InvalidSignature {}),
+    }
+}
+
+/// Account addresses are the bech32 encoding of ripemd160(sha256(pubkey))
+fn is_signer(address: &str, pubkey: &[u8]) -> bool {
+    let data = match bech32::decode(address) {
+        Ok((_, data, _)) => data,
+        Err(_) => return false,
+    };
+    match Vec::<u8>::from_base32(&data) {
+        Ok(hash) => hash == Ripemd160::digest(&Sha256::digest(pubkey)).as_slice(),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+
+use cosmwasm_std::{
+    Coin, Deps, DepsMut, Env, MessageInfo, Order, Pair, StdResult, Storage, SubMsg,
+};
+
+use crate::contract::execute;
+use crate::msg::{ExecuteMsg, SimulateResponse};
+
+/// Runs an execute message against a throwaway copy of the state
+pub fn simulate(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    funds: Vec<Coin>,
+    msg: ExecuteMsg,
+) -> StdResult<SimulateResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let mut storage = SimulationStorage::new(deps.storage);
+    let simulated_deps = DepsMut {
+        storage: &mut storage,
+        api: deps.api,
+        querier: deps.querier,
+    };
+    let info = MessageInfo { sender, funds };
+
+    Ok(match execute(simulated_deps, env, info, msg) {
+        Ok(response) => SimulateResponse {
+            error: None,
+            messages: response.messages,
+            attributes: response.attributes,
+            events: response.events,
+        },
+        Err(err) => SimulateResponse {
+            error: Some(err.to_string()),
+            messages: Vec::<SubMsg>::new(),
+            attributes: vec![],
+            events: vec![],
+        },
+    })
+}
+
+/// Reads through to the contract storage, writes are kept in memory and dropped afterwards
+struct SimulationStorage<'a> {
+    base: &'a dyn Storage,
+    /// Written values, `None` for removed keys
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> SimulationStorage<'a> {
+    fn new(base: &'a dyn Storage) -> Self {
+        SimulationStorage {
+            base,
+            changes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'a> Storage for SimulationStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.changes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Pair> + 'b> {
+        let base = self.base.range(start, end, order);
+        let lower = start.unwrap_or_default().to_vec();
+        let changes: Box<dyn Iterator<Item = ChangedPair<'b>>> = match end {
+            // Empty ranges are allowed by the trait but make BTreeMap panic
+            Some(end) if end <= lower.as_slice() => Box::new(std::iter::empty()),
+            Some(end) => {
+                let range = self.changes.range(lower..end.to_vec());
+                match order {
+                    Order::Ascending => Box::new(range),
+                    Order::Descending => Box::new(range.rev()),
+                }
+            }
+            None => {
+                let range = self.changes.range(lower..);
+                match order {
+                    Order::Ascending => Box::new(range),
+                    Order::Descending => Box::new(range.rev()),
+                }
+            }
+        };
+        Box::new(MergedRange {
+            base: base.peekable(),
+            changes: changes.peekable(),
+            order,
+        })
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.changes.insert(key.to_vec(), None);
+    }
+}
+
+type ChangedPair<'a> = (&'a Vec<u8>, &'a Option<Vec<u8>>);
+
+/// Range over the contract storage with the simulated changes applied on top
+struct MergedRange<'a> {
+    base: Peekable<Box<dyn Iterator<Item = Pair> + 'a>>,
+    changes: Peekable<Box<dyn Iterator<Item = ChangedPair<'a>> + 'a>>,
+    order: Order,
+}
+
+impl<'a> Iterator for MergedRange<'a> {
+    type Item = Pair;
+
+    fn next(&mut self) -> Option<Pair> {
+        loop {
+            let next = match (self.base.peek(), self.changes.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((base_key, _)), Some((changed_key, _))) => match self.order {
+                    Order::Ascending => base_key.cmp(changed_key),
+                    Order::Descending => changed_key.as_slice().cmp(base_key.as_slice()),
+                },
+            };
+            // Changed keys shadow the stored ones
+            if next == Ordering::Less {
+                return self.base.next();
+            }
+            if next == Ordering::Equal {
+                self.base.next();
+            }
+            if let Some((key, Some(value))) = self.changes.next() {
+                return Some((key.clone(), value.clone()));
+            }
+        }
+    }
+}
@@ -1,13 +1,155 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use std::convert::TryInto;
+
+use cosmwasm_std::{Addr, Binary, BlockInfo, Empty, Order, StdError, StdResult, Storage};
+use cw721::Expiration;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
+
+use crate::error::ContractError;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Swap {
     pub lhs: SwapSide,
     pub rhs: Option<SwapSide>,
+    pub status: SwapStatus,
+    pub expires: Expiration,
+    /// Settlement transfers that failed, their NFTs are still held by the contract
+    #[serde(default)]
+    pub failed_transfers: Vec<SwapRole>,
+    #[serde(default)]
+    pub custody: Custody,
+}
+
+/// Where the NFTs of a swap wait for settlement.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Custody {
+    /// Transferred to the contract when listed or offered
+    Escrow,
+    /// Kept by their owners, who approve the contract to transfer them at settlement
+    Approval,
+}
+
+impl Default for Custody {
+    fn default() -> Self {
+        Custody::Escrow
+    }
+}
+
+impl Swap {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+
+    /// NFTs held by the contract for this swap
+    pub fn escrowed_nfts(&self) -> u64 {
+        match (self.status, &self.rhs) {
+            _ if self.custody == Custody::Approval => 0,
+            (status, _) if status.is_terminal() => 0,
+            (SwapStatus::SettlementFailed, _) => self.failed_transfers.len() as u64,
+            (_, Some(_)) => 2,
+            (_, None) => 1,
+        }
+    }
+
+    /// Whether the NFT of the given side is still tied to the swap, with approval custody
+    /// it stays in its owner's wallet meanwhile
+    pub fn holds(&self, role: SwapRole) -> bool {
+        match self.status {
+            SwapStatus::SettlementFailed => self.failed_transfers.contains(&role),
+            status => !status.is_terminal(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapStatus {
+    /// Waiting for an offer
+    Open,
+    /// Offer made, waiting for the lhs owner to accept it
+    Offered,
+    Completed,
+    Cancelled,
+    Expired,
+    /// At least one settlement transfer failed, see `Swap::failed_transfers`
+    SettlementFailed,
+    /// With approval custody, an owner no longer held their NFT or had revoked the approval
+    /// at finalize. Nothing was transferred.
+    Broken,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapAction {
+    Respond,
+    Accept,
+    Reject,
+    Withdraw,
+    Cancel,
+    Expire,
+    /// Sends the NFTs of a failed settlement again
+    Retry,
+    /// Returns the NFTs of a failed settlement to their depositors
+    Unwind,
+    /// Ends an accepted swap whose NFTs under approval custody can no longer be transferred
+    Break,
+    /// Records a settlement transfer reported failed by its reply, not sent by users
+    FailSettlement,
+}
+
+impl SwapStatus {
+    /// Same spelling as in messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapStatus::Open => "open",
+            SwapStatus::Offered => "offered",
+            SwapStatus::Completed => "completed",
+            SwapStatus::Cancelled => "cancelled",
+            SwapStatus::Expired => "expired",
+            SwapStatus::SettlementFailed => "settlement_failed",
+            SwapStatus::Broken => "broken",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            SwapStatus::Completed
+                | SwapStatus::Cancelled
+                | SwapStatus::Expired
+                | SwapStatus::Broken
+        )
+    }
+
+    /// The single place that decides which status changes are allowed.
+    pub fn transition(self, action: SwapAction) -> Result<SwapStatus, ContractError> {
+        match (self, action) {
+            (SwapStatus::Open, SwapAction::Respond) => Ok(SwapStatus::Offered),
+            (SwapStatus::Open, SwapAction::Accept) => Err(ContractError::SwapNotResponded {}),
+            (SwapStatus::Offered, SwapAction::Accept) => Ok(SwapStatus::Completed),
+            (SwapStatus::Offered, SwapAction::Reject) => Ok(SwapStatus::Open),
+            (SwapStatus::Offered, SwapAction::Withdraw) => Ok(SwapStatus::Open),
+            (SwapStatus::Offered, SwapAction::Break) => Ok(SwapStatus::Broken),
+            (SwapStatus::SettlementFailed, SwapAction::Retry) => Ok(SwapStatus::Completed),
+            (SwapStatus::SettlementFailed, SwapAction::Unwind) => Ok(SwapStatus::Cancelled),
+            // Each failed transfer replies separately, so the second one finds the swap failed
+            (SwapStatus::Completed, SwapAction::FailSettlement)
+            | (SwapStatus::SettlementFailed, SwapAction::FailSettlement) => {
+                Ok(SwapStatus::SettlementFailed)
+            }
+            // Part of the settlement may have been delivered, refunding could pay twice
+            (SwapStatus::SettlementFailed, action) => Err(ContractError::InvalidSwapTransition {
+                status: SwapStatus::SettlementFailed,
+                action,
+            }),
+            (status, SwapAction::Cancel) if !status.is_terminal() => Ok(SwapStatus::Cancelled),
+            (status, SwapAction::Expire) if !status.is_terminal() => Ok(SwapStatus::Expired),
+            (status, action) => Err(ContractError::InvalidSwapTransition { status, action }),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -17,10 +159,391 @@ pub struct SwapSide {
     pub token_id: String,
 }
 
-pub const SWAPS: Map<String, Swap> = Map::new("swaps");
+pub const SWAPS_NAMESPACE: &str = "swaps";
+
+/// Index key of a single value, followed by the swap primary key
+type SwapIndexKey = (Vec<u8>, Vec<u8>);
+/// Index key of a (collection, token id) pair, followed by the swap primary key
+type SwapTokenIndexKey = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+pub struct SwapIndexes<'a> {
+    pub lhs_owner: MultiIndex<'a, SwapIndexKey, Swap>,
+    /// Swaps without an offer are indexed under an empty owner
+    pub rhs_owner: MultiIndex<'a, SwapIndexKey, Swap>,
+    /// Collection of the listed NFT
+    pub collection: MultiIndex<'a, SwapIndexKey, Swap>,
+    /// Collection and token id of the listed NFT
+    pub token: MultiIndex<'a, SwapTokenIndexKey, Swap>,
+    /// Collection and token id of the offered NFT, empty for swaps without an offer
+    pub offer_token: MultiIndex<'a, SwapTokenIndexKey, Swap>,
+    pub status: MultiIndex<'a, SwapIndexKey, Swap>,
+    /// Listing owner of unfinished swaps, finished ones are indexed under an empty owner
+    pub active_lhs_owner: MultiIndex<'a, SwapIndexKey, Swap>,
+    /// Offer owner of unfinished swaps, finished ones and swaps without an offer are indexed
+    /// under an empty owner
+    pub active_rhs_owner: MultiIndex<'a, SwapIndexKey, Swap>,
+}
+
+impl<'a> IndexList<Swap> for SwapIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Swap>> + '_> {
+        let v: Vec<&dyn Index<Swap>> = vec![
+            &self.lhs_owner,
+            &self.rhs_owner,
+            &self.collection,
+            &self.token,
+            &self.offer_token,
+            &self.status,
+            &self.active_lhs_owner,
+            &self.active_rhs_owner,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn swaps<'a>() -> IndexedMap<'a, U64Key, Swap, SwapIndexes<'a>> {
+    let indexes = SwapIndexes {
+        lhs_owner: MultiIndex::new(
+            |swap, pk| (swap.lhs.owner.as_bytes().to_vec(), pk),
+            SWAPS_NAMESPACE,
+            "swaps__lhs_owner",
+        ),
+        rhs_owner: MultiIndex::new(
+            |swap, pk| {
+                let owner = swap.rhs.as_ref().map(|rhs| rhs.owner.as_bytes().to_vec());
+                (owner.unwrap_or_default(), pk)
+            },
+            SWAPS_NAMESPACE,
+            "swaps__rhs_owner",
+        ),
+        collection: MultiIndex::new(
+            |swap, pk| (swap.lhs.collection.as_bytes().to_vec(), pk),
+            SWAPS_NAMESPACE,
+            "swaps__collection",
+        ),
+        token: MultiIndex::new(
+            |swap, pk| {
+                (
+                    swap.lhs.collection.as_bytes().to_vec(),
+                    swap.lhs.token_id.as_bytes().to_vec(),
+                    pk,
+                )
+            },
+            SWAPS_NAMESPACE,
+            "swaps__token",
+        ),
+        offer_token: MultiIndex::new(
+            |swap, pk| match &swap.rhs {
+                Some(rhs) => (
+                    rhs.collection.as_bytes().to_vec(),
+                    rhs.token_id.as_bytes().to_vec(),
+                    pk,
+                ),
+                None => (vec![], vec![], pk),
+            },
+            SWAPS_NAMESPACE,
+            "swaps__offer_token",
+        ),
+        status: MultiIndex::new(
+            |swap, pk| (swap.status.as_str().as_bytes().to_vec(), pk),
+            SWAPS_NAMESPACE,
+            "swaps__status",
+        ),
+        active_lhs_owner: MultiIndex::new(
+            |swap, pk| match swap.status.is_terminal() {
+                false => (swap.lhs.owner.as_bytes().to_vec(), pk),
+                true => (vec![], pk),
+            },
+            SWAPS_NAMESPACE,
+            "swaps__active_lhs_owner",
+        ),
+        active_rhs_owner: MultiIndex::new(
+            |swap, pk| match (&swap.rhs, swap.status.is_terminal()) {
+                (Some(rhs), false) => (rhs.owner.as_bytes().to_vec(), pk),
+                _ => (vec![], pk),
+            },
+            SWAPS_NAMESPACE,
+            "swaps__active_rhs_owner",
+        ),
+    };
+    IndexedMap::new(SWAPS_NAMESPACE, indexes)
+}
+
+/// Decodes a swap id from a raw `swaps()` primary key, or any other raw `U64Key` such as the
+/// claim ids and nonces within an address prefix of `CLAIMS` and `USED_NONCES`
+pub fn swap_id_from_key(key: &[u8]) -> StdResult<u64> {
+    let bytes: [u8; 8] = key
+        .try_into()
+        .map_err(|_| StdError::generic_err("Invalid swap key"))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Saves the swap and keeps the stats in step with its status
+pub fn save_swap(storage: &mut dyn Storage, swap_id: u64, swap: &Swap) -> StdResult<()> {
+    let previous = swaps().may_load(storage, swap_id.into())?;
+
+    let mut stats = STATS.load(storage)?;
+    if let Some(previous) = &previous {
+        stats.remove(previous);
+    }
+    stats.add(swap);
+    STATS.save(storage, &stats)?;
+
+    // A failed settlement still counts as settled until it is unwound
+    let settled = |swap: &Swap| {
+        matches!(
+            swap.status,
+            SwapStatus::Completed | SwapStatus::SettlementFailed
+        )
+    };
+    match (
+        previous.as_ref().map(settled).unwrap_or(false),
+        settled(swap),
+    ) {
+        (false, true) => record_settlement(storage, swap)?,
+        (true, false) => revert_settlement(storage, swap)?,
+        _ => (),
+    }
+
+    swaps().save(storage, swap_id.into(), swap)
+}
+
+/// Counts a completed swap once for each collection it involves
+pub fn record_settlement(storage: &mut dyn Storage, swap: &Swap) -> StdResult<()> {
+    update_settled(storage, swap, |settled| settled + 1)
+}
+
+/// Takes back `record_settlement` for an unwound swap
+fn revert_settlement(storage: &mut dyn Storage, swap: &Swap) -> StdResult<()> {
+    update_settled(storage, swap, |settled| settled - 1)
+}
+
+fn update_settled(
+    storage: &mut dyn Storage,
+    swap: &Swap,
+    update: impl Fn(u64) -> u64,
+) -> StdResult<()> {
+    let mut collections = vec![&swap.lhs.collection];
+    if let Some(rhs) = &swap.rhs {
+        if rhs.collection != swap.lhs.collection {
+            collections.push(&rhs.collection);
+        }
+    }
+    for collection in collections {
+        COLLECTION_STATS.update(storage, collection, |stats| -> StdResult<_> {
+            let mut stats = stats.unwrap_or_default();
+            stats.settled = update(stats.settled);
+            Ok(stats)
+        })?;
+    }
+    Ok(())
+}
+
+/// Side of a swap an NFT is escrowed for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapRole {
+    Listing,
+    Offer,
+}
+
+/// Finds the swap currently holding the NFT, finished swaps no longer hold anything. Under
+/// approval custody the NFT stays with its owner, who remains free to move it, so only escrowed
+/// NFTs count.
+pub fn escrowed_token(
+    storage: &dyn Storage,
+    collection: &Addr,
+    token_id: &str,
+) -> StdResult<Option<(u64, SwapRole)>> {
+    let swaps = swaps();
+    let prefix = (collection.as_bytes().to_vec(), token_id.as_bytes().to_vec());
+    let indexes = [
+        (&swaps.idx.token, SwapRole::Listing),
+        (&swaps.idx.offer_token, SwapRole::Offer),
+    ];
+    for (index, role) in &indexes {
+        for item in index
+            .prefix(prefix.clone())
+            .range(storage, None, None, Order::Descending)
+        {
+            let (key, swap) = item?;
+            if swap.custody == Custody::Escrow && swap.holds(*role) {
+                return Ok(Some((swap_id_from_key(&key)?, *role)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Id of the last swap visited by `RefundAll`
+pub const REFUND_PROGRESS: Item<u64> = Item::new("refund_progress");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub next_swap_id: u32,
+    pub next_swap_id: u64,
+    pub admin: Addr,
+    pub shutdown: bool,
+    pub collection_mode: CollectionMode,
+    #[serde(default)]
+    pub payout_mode: PayoutMode,
 }
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Decides which collections may be escrowed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionMode {
+    /// Every collection is accepted
+    Open,
+    /// Only collections from `ALLOWED_COLLECTIONS` are accepted
+    Allowlist,
+    /// Collections from `DENIED_COLLECTIONS` are rejected
+    Denylist,
+}
+
+/// How NFTs leave the contract when a swap ends.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutMode {
+    /// Transferred to the recipient right away
+    Push,
+    /// Credited to `CLAIMS`, the recipient withdraws them with `Claim`
+    Claim,
+}
+
+impl Default for PayoutMode {
+    fn default() -> Self {
+        PayoutMode::Push
+    }
+}
+
+/// Asset owed to an address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Claimable {
+    Nft { collection: Addr, token_id: String },
+}
+
+/// Pending claims by recipient and claim id
+pub const CLAIMS: Map<(&Addr, U64Key), Claimable> = Map::new("claims");
+pub const NEXT_CLAIM_ID: Item<u64> = Item::new("next_claim_id");
+
+pub fn credit_claim(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    claim: &Claimable,
+) -> StdResult<()> {
+    let claim_id = NEXT_CLAIM_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_CLAIM_ID.save(storage, &(claim_id + 1))?;
+    CLAIMS.save(storage, (recipient, claim_id.into()), claim)
+}
+
+/// NFTs the contract believes it holds, by collection and token id. Coins are not recorded,
+/// execute messages refuse funds and nothing else deposits any.
+pub const LEDGER_NFTS: Map<(&Addr, &str), Empty> = Map::new("ledger_nfts");
+
+pub fn ledger_deposit_nft(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    token_id: &str,
+) -> StdResult<()> {
+    LEDGER_NFTS.save(storage, (collection, token_id), &Empty {})
+}
+
+pub fn ledger_withdraw_nft(storage: &mut dyn Storage, collection: &Addr, token_id: &str) {
+    LEDGER_NFTS.remove(storage, (collection, token_id))
+}
+
+/// Nonces of signed offers that were executed or cancelled, by signer and nonce
+pub const USED_NONCES: Map<(&Addr, U64Key), Empty> = Map::new("used_nonces");
+/// Signed offers with a lower nonce are void, set by `IncrementMinNonce`
+pub const MIN_NONCES: Map<&Addr, u64> = Map::new("min_nonces");
+
+/// NFT escrowed under a sha256 hashlock, released to `recipient` by the preimage or returned
+/// to `depositor` once expired
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Htlc {
+    pub depositor: Addr,
+    pub recipient: Addr,
+    pub collection: Addr,
+    pub token_id: String,
+    /// sha256 of the preimage
+    pub hashlock: Binary,
+    pub expires: Expiration,
+    pub status: HtlcStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HtlcStatus {
+    Locked,
+    /// Released to the recipient, the preimage is in `PREIMAGES`
+    Claimed,
+    /// Returned to the depositor after expiry
+    Refunded,
+}
+
+impl HtlcStatus {
+    /// Same spelling as in messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HtlcStatus::Locked => "locked",
+            HtlcStatus::Claimed => "claimed",
+            HtlcStatus::Refunded => "refunded",
+        }
+    }
+}
+
+pub const HTLCS: Map<U64Key, Htlc> = Map::new("htlcs");
+pub const NEXT_HTLC_ID: Item<u64> = Item::new("next_htlc_id");
+/// Revealed preimages by hashlock, for completing the leg on the other chain
+pub const PREIMAGES: Map<&[u8], Binary> = Map::new("preimages");
+
+pub const ALLOWED_COLLECTIONS: Map<&Addr, Empty> = Map::new("allowed_collections");
+pub const DENIED_COLLECTIONS: Map<&Addr, Empty> = Map::new("denied_collections");
+
+/// Running counters, updated on every swap save
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Stats {
+    pub open: u64,
+    pub offered: u64,
+    pub completed: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    #[serde(default)]
+    pub settlement_failed: u64,
+    #[serde(default)]
+    pub broken: u64,
+    pub escrowed_nfts: u64,
+}
+
+impl Stats {
+    pub fn add(&mut self, swap: &Swap) {
+        *self.status_counter(swap.status) += 1;
+        self.escrowed_nfts += swap.escrowed_nfts();
+    }
+
+    pub fn remove(&mut self, swap: &Swap) {
+        *self.status_counter(swap.status) -= 1;
+        self.escrowed_nfts -= swap.escrowed_nfts();
+    }
+
+    fn status_counter(&mut self, status: SwapStatus) -> &mut u64 {
+        match status {
+            SwapStatus::Open => &mut self.open,
+            SwapStatus::Offered => &mut self.offered,
+            SwapStatus::Completed => &mut self.completed,
+            SwapStatus::Cancelled => &mut self.cancelled,
+            SwapStatus::Expired => &mut self.expired,
+            SwapStatus::SettlementFailed => &mut self.settlement_failed,
+            SwapStatus::Broken => &mut self.broken,
+        }
+    }
+}
+pub const STATS: Item<Stats> = Item::new("stats");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionStats {
+    /// Completed swaps involving the collection
+    pub settled: u64,
+}
+pub const COLLECTION_STATS: Map<&Addr, CollectionStats> = Map::new("collection_stats");
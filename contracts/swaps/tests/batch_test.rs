@@ -0,0 +1,151 @@
+mod common;
+
+use cosmwasm_std::{attr, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StatsResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn initiate_swap(collection: &str, token_id: &str) -> ExecuteMsg {
+        ExecuteMsg::InitiateSwap {
+            collection: String::from(collection),
+            token_id: String::from(token_id),
+            expires: None,
+            custody: None,
+        }
+    }
+
+    fn batch(deps: &mut Cw721Deps, msgs: Vec<ExecuteMsg>) -> Result<(), ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            ExecuteMsg::Batch { msgs },
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn list_several_nfts_at_once() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let batch_msg = ExecuteMsg::Batch {
+            msgs: ["1", "2", "3"]
+                .iter()
+                .map(|token_id| initiate_swap("gp_collection", token_id))
+                .collect(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            batch_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(res.events.len(), 3);
+        assert!(res.events.iter().all(|event| event.ty == "swap_created"));
+        assert_eq!(
+            res.attributes[..4],
+            [
+                attr("method", "batch"),
+                attr("count", "3"),
+                attr("method", "create_swap"),
+                attr("swap_id", "1"),
+            ]
+        );
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.open, 3);
+
+        // Every message runs as the sender
+        let batch_msg = ExecuteMsg::Batch {
+            msgs: vec![
+                ExecuteMsg::CancelSwap {
+                    swap_id: Uint64::new(1),
+                },
+                ExecuteMsg::CancelSwap {
+                    swap_id: Uint64::new(2),
+                },
+            ],
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            batch_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            batch_msg,
+        )
+        .unwrap();
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.open, 1);
+        assert_eq!(stats.cancelled, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_batches() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // One failure fails the whole batch
+        let msgs = vec![
+            initiate_swap("gp_collection", "1"),
+            initiate_swap("goochi-goochi", "2"),
+        ];
+        match batch(&mut deps, msgs) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::NotTokenOwner {
+                    collection: String::from("goochi-goochi"),
+                    token_id: String::from("2"),
+                }
+            ),
+        };
+
+        let msgs = (0..31)
+            .map(|token_id| initiate_swap("gp_collection", &token_id.to_string()))
+            .collect();
+        match batch(&mut deps, msgs) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::BatchTooLarge { max: 30 }),
+        };
+
+        let msgs = vec![ExecuteMsg::Batch { msgs: vec![] }];
+        match batch(&mut deps, msgs) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NestedBatch {}),
+        };
+
+        Ok(())
+    }
+}
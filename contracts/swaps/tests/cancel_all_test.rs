@@ -0,0 +1,172 @@
+mod common;
+
+use cosmwasm_std::{attr, Deps, Response, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StatsResponse, SwapResponse};
+use swaps::state::SwapStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn get_swap(deps: Deps, swap_id: u64) -> SwapResponse {
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(swap_id),
+        };
+        from_binary(&query(deps, mock_env(), get_swap_msg).unwrap()).unwrap()
+    }
+
+    fn initiate_swap(deps: &mut Cw721Deps, sender: &str, collection: &str, token_id: &str) {
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from(collection),
+            token_id: String::from(token_id),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+    }
+
+    fn swap_reply(
+        deps: &mut Cw721Deps,
+        sender: &str,
+        swap_id: u64,
+        collection: &str,
+        token_id: &str,
+    ) {
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(swap_id),
+            collection: String::from(collection),
+            token_id: String::from(token_id),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+    }
+
+    fn cancel_all(deps: &mut Cw721Deps, limit: Option<u32>) -> Response {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            ExecuteMsg::CancelAll { limit },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cancel_listings_and_offers() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Swaps 1 to 3 are the swapper's listings, the first one has an offer
+        for token_id in &["1", "2", "3"] {
+            initiate_swap(&mut deps, "swapper", "gp_collection", token_id);
+        }
+        swap_reply(&mut deps, "responder", 1, "goochi-goochi", "xyz");
+        // The swapper offers on Swap 4
+        initiate_swap(&mut deps, "responder", "goochi-goochi", "abc");
+        swap_reply(&mut deps, "swapper", 4, "gp_collection", "4");
+
+        // Cancelling Swap 1 returns both NFTs
+        let res = cancel_all(&mut deps, Some(2));
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "cancel_all"),
+                attr("listings", "2"),
+                attr("offers", "0"),
+                attr("done", "false"),
+            ]
+        );
+        assert_eq!(get_swap(deps.as_ref(), 3).status, SwapStatus::Open);
+
+        // Resumes with Swap 3, then the offer on Swap 4
+        let res = cancel_all(&mut deps, None);
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "cancel_all"),
+                attr("listings", "1"),
+                attr("offers", "1"),
+                attr("done", "true"),
+            ]
+        );
+        assert_eq!(res.events[1].ty, "offer_withdrawn");
+        let swap = get_swap(deps.as_ref(), 4);
+        assert_eq!(swap.status, SwapStatus::Open);
+        assert_eq!(swap.rhs, None);
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.cancelled, 3);
+        assert_eq!(stats.open, 1);
+
+        // Nothing left to cancel
+        let res = cancel_all(&mut deps, None);
+        assert_eq!(res.messages, vec![]);
+        assert!(res.attributes.contains(&attr("done", "true")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cancel_swaps_made_between_calls() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // The swapper offers on Swaps 2 and 3
+        for token_id in &["a", "b", "c"] {
+            initiate_swap(&mut deps, "responder", "goochi-goochi", token_id);
+        }
+        swap_reply(&mut deps, "swapper", 2, "gp_collection", "2");
+        swap_reply(&mut deps, "swapper", 3, "gp_collection", "3");
+
+        let res = cancel_all(&mut deps, Some(1));
+        assert!(res.attributes.contains(&attr("done", "false")));
+        assert_eq!(get_swap(deps.as_ref(), 2).rhs, None);
+
+        // Meanwhile the swapper offers on the older Swap 1 and lists Swap 4
+        swap_reply(&mut deps, "swapper", 1, "gp_collection", "1");
+        initiate_swap(&mut deps, "swapper", "gp_collection", "4");
+
+        let res = cancel_all(&mut deps, None);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "cancel_all"),
+                attr("listings", "1"),
+                attr("offers", "2"),
+                attr("done", "true"),
+            ]
+        );
+        assert_eq!(get_swap(deps.as_ref(), 4).status, SwapStatus::Cancelled);
+        assert_eq!(get_swap(deps.as_ref(), 1).rhs, None);
+        assert_eq!(get_swap(deps.as_ref(), 3).rhs, None);
+
+        Ok(())
+    }
+}
@@ -1,29 +1,33 @@
-use cosmwasm_std::StdError;
+mod common;
+
+use cosmwasm_std::Uint64;
 
 use swaps::contract::{execute, instantiate, query};
 use swaps::error::ContractError;
 use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
+use swaps::state::{SwapAction, SwapStatus};
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_env, mock_info};
     #[allow(unused_imports)]
     use pretty_assertions::{assert_eq, assert_ne};
 
     #[test]
     fn cancel_swap() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(2, "token"));
+        let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         // When Swap does not exists
         // Cancel Swap returns an error
         let cancel_swap_msg = ExecuteMsg::CancelSwap {
-            swap_id: String::from("1"),
+            swap_id: Uint64::new(1),
         };
         match execute(
             deps.as_mut(),
@@ -36,47 +40,73 @@ mod tests {
         };
 
         // Initiate Swap
-        let swapper_info = mock_info("swapper", &coins(2, "token"));
+        let swapper_info = mock_info("swapper", &[]);
         let create_swap_msg = ExecuteMsg::InitiateSwap {
             collection: String::from("gp_collection"),
             token_id: String::from("123"),
+            expires: None,
+            custody: None,
         };
-        let swap_created =
-            execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
+        let swap_created = execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg,
+        )
+        .unwrap();
         let created_swap_id = swap_created
             .attributes
             .iter()
-            .find(move |x| x.key == String::from("swap_id"))
+            .find(move |x| x.key == "swap_id")
             .unwrap()
             .value
-            .clone();
+            .parse()
+            .map(Uint64::new)
+            .unwrap();
 
         let get_swap_msg = QueryMsg::GetSwap {
-            swap_id: created_swap_id.clone(),
+            swap_id: created_swap_id,
         };
 
         let get_swap_response = query(deps.as_ref(), mock_env(), get_swap_msg.clone()).unwrap();
         let _: SwapResponse = from_binary(&get_swap_response).unwrap();
 
-        // Cancel Swap
+        // Only the Swap owner can cancel it
         match execute(
             deps.as_mut(),
             mock_env(),
             info.clone(),
             cancel_swap_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        // Cancel Swap
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            cancel_swap_msg.clone(),
         ) {
             Ok(_) => (),
             Err(e) => panic!("Unexpected error: {:#?}", e),
         };
 
         // When Swap is canceled
-        // Fetch Swap returns an error
-        match query(deps.as_ref(), mock_env(), get_swap_msg.clone()) {
+        // Fetch Swap returns it as cancelled
+        let get_swap_response = query(deps.as_ref(), mock_env(), get_swap_msg.clone()).unwrap();
+        let swap_response: SwapResponse = from_binary(&get_swap_response).unwrap();
+        assert_eq!(swap_response.status, SwapStatus::Cancelled);
+
+        // Cancelled Swap can't be cancelled again
+        match execute(deps.as_mut(), mock_env(), swapper_info, cancel_swap_msg) {
             Ok(_) => panic!("Error expected"),
             Err(err) => assert_eq!(
                 err,
-                StdError::NotFound {
-                    kind: String::from("Swap")
+                ContractError::InvalidSwapTransition {
+                    status: SwapStatus::Cancelled,
+                    action: SwapAction::Cancel,
                 }
             ),
         };
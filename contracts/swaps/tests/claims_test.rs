@@ -0,0 +1,205 @@
+mod common;
+
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Deps, SubMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg, PendingClaim, PendingClaimsResponse, QueryMsg};
+use swaps::state::{Claimable, PayoutMode};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{attr, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn pending_claims(deps: Deps, address: &str, limit: Option<u32>) -> PendingClaimsResponse {
+        let pending_claims_msg = QueryMsg::PendingClaims {
+            address: String::from(address),
+            start_after: None,
+            limit,
+        };
+        from_binary(&query(deps, mock_env(), pending_claims_msg).unwrap()).unwrap()
+    }
+
+    fn nft(collection: &str, token_id: &str) -> Claimable {
+        Claimable::Nft {
+            collection: Addr::unchecked(collection),
+            token_id: String::from(token_id),
+        }
+    }
+
+    fn transfer(collection: &str, token_id: &str, recipient: &str) -> SubMsg {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(collection),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: String::from(recipient),
+                token_id: String::from(token_id),
+            })
+            .unwrap(),
+        }))
+    }
+
+    #[test]
+    fn claim_settled_and_refunded_nfts() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        // Only the admin can switch to claims
+        let update_payout_mode_msg = ExecuteMsg::UpdatePayoutMode {
+            mode: PayoutMode::Claim,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            update_payout_mode_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            update_payout_mode_msg,
+        )
+        .unwrap();
+
+        // Swap 1 gets settled, Swap 2 cancelled
+        for token_id in &["1", "2"] {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: String::from(*token_id),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            finalize_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(2),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            cancel_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        assert_eq!(
+            pending_claims(deps.as_ref(), "swapper", None),
+            PendingClaimsResponse {
+                claims: vec![
+                    PendingClaim {
+                        claim_id: Uint64::new(2),
+                        claim: nft("goochi-goochi", "abc"),
+                    },
+                    PendingClaim {
+                        claim_id: Uint64::new(3),
+                        claim: nft("gp_collection", "2"),
+                    },
+                ],
+                next: None,
+            }
+        );
+        assert_eq!(
+            pending_claims(deps.as_ref(), "responder", Some(1)),
+            PendingClaimsResponse {
+                claims: vec![PendingClaim {
+                    claim_id: Uint64::new(1),
+                    claim: nft("gp_collection", "1"),
+                }],
+                next: Some(Uint64::new(1)),
+            }
+        );
+
+        // Claims are withdrawn oldest first, up to the limit
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            ExecuteMsg::Claim { limit: Some(1) },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![transfer("goochi-goochi", "abc", "swapper")]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "claim"),
+                attr("claimed", "1"),
+                attr("done", "false"),
+            ]
+        );
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![transfer("gp_collection", "2", "swapper")]
+        );
+        assert_eq!(
+            pending_claims(deps.as_ref(), "swapper", None).claims,
+            vec![]
+        );
+
+        // Nothing left
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            ExecuteMsg::Claim { limit: None },
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NothingToClaim {}),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use cosmwasm_std::{Addr, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{CollectionsResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use swaps::state::CollectionMode;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    #[test]
+    fn only_admin_can_manage_collections() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let stranger_info = mock_info("stranger", &[]);
+        let msgs = vec![
+            ExecuteMsg::UpdateCollectionMode {
+                mode: CollectionMode::Allowlist,
+            },
+            ExecuteMsg::UpdateAllowedCollections {
+                add: vec![String::from("gp_collection")],
+                remove: vec![],
+            },
+            ExecuteMsg::UpdateDeniedCollections {
+                add: vec![String::from("scam_collection")],
+                remove: vec![],
+            },
+        ];
+        for msg in msgs {
+            match execute(deps.as_mut(), mock_env(), stranger_info.clone(), msg) {
+                Ok(_) => panic!("Error expected"),
+                Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+            };
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn allowlist() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        let allow_msg = ExecuteMsg::UpdateAllowedCollections {
+            add: vec![String::from("gp_collection")],
+            remove: vec![],
+        };
+        execute(deps.as_mut(), mock_env(), creator_info.clone(), allow_msg).unwrap();
+        let mode_msg = ExecuteMsg::UpdateCollectionMode {
+            mode: CollectionMode::Allowlist,
+        };
+        execute(deps.as_mut(), mock_env(), creator_info, mode_msg).unwrap();
+
+        // Initiate Swap with allowed collection
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
+
+        // Reply with collection that is not allowed
+        let responder_info = mock_info("responder", &[]);
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        match execute(deps.as_mut(), mock_env(), responder_info, swap_reply_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::CollectionNotAllowed {
+                    collection: String::from("goochi-goochi")
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn denylist() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        let deny_msg = ExecuteMsg::UpdateDeniedCollections {
+            add: vec![String::from("scam_collection")],
+            remove: vec![],
+        };
+        execute(deps.as_mut(), mock_env(), creator_info.clone(), deny_msg).unwrap();
+
+        // Denylist is ignored until its mode is enabled
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("scam_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg.clone(),
+        )
+        .unwrap();
+
+        let mode_msg = ExecuteMsg::UpdateCollectionMode {
+            mode: CollectionMode::Denylist,
+        };
+        execute(deps.as_mut(), mock_env(), creator_info.clone(), mode_msg).unwrap();
+
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::CollectionNotAllowed {
+                    collection: String::from("scam_collection")
+                }
+            ),
+        };
+
+        // Removed from denylist
+        let undeny_msg = ExecuteMsg::UpdateDeniedCollections {
+            add: vec![],
+            remove: vec![String::from("scam_collection")],
+        };
+        execute(deps.as_mut(), mock_env(), creator_info, undeny_msg).unwrap();
+        let create_other_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("scam_collection"),
+            token_id: String::from("456"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info,
+            create_other_swap_msg,
+        )
+        .unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_collections() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        let allow_msg = ExecuteMsg::UpdateAllowedCollections {
+            add: vec![
+                String::from("collection_c"),
+                String::from("collection_a"),
+                String::from("collection_b"),
+            ],
+            remove: vec![],
+        };
+        execute(deps.as_mut(), mock_env(), creator_info, allow_msg).unwrap();
+
+        let first_page_msg = QueryMsg::AllowedCollections {
+            start_after: None,
+            limit: Some(2),
+        };
+        let first_page: CollectionsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), first_page_msg).unwrap()).unwrap();
+        assert_eq!(
+            first_page.collections,
+            vec![
+                Addr::unchecked("collection_a"),
+                Addr::unchecked("collection_b")
+            ]
+        );
+
+        let second_page_msg = QueryMsg::AllowedCollections {
+            start_after: Some(String::from("collection_b")),
+            limit: Some(2),
+        };
+        let second_page: CollectionsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), second_page_msg).unwrap()).unwrap();
+        assert_eq!(
+            second_page.collections,
+            vec![Addr::unchecked("collection_c")]
+        );
+
+        let denied_msg = QueryMsg::DeniedCollections {
+            start_after: None,
+            limit: None,
+        };
+        let denied: CollectionsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), denied_msg).unwrap()).unwrap();
+        assert_eq!(denied.collections, Vec::<Addr>::new());
+
+        Ok(())
+    }
+}
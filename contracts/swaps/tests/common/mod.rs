@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+
+use bech32::{ToBase32, Variant};
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{
+    from_slice, to_binary, to_vec, Binary, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use cw721::{
+    Approval, ApprovedForAllResponse, Cw721QueryMsg, Expiration, NumTokensResponse,
+    OwnerOfResponse, TokensResponse,
+};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+use swaps::msg::SignedOffer;
+
+pub type Cw721Deps = OwnedDeps<MockStorage, MockApi, Cw721Querier>;
+
+type Owners = Box<dyn Fn(&str, &str) -> Option<String>>;
+
+/// Answers cw721 queries for any collection. Owners come from a lookup function, the swap
+/// contract is approved for every token unless told otherwise.
+pub struct Cw721Querier {
+    pub owners: Owners,
+    pub token_approval: bool,
+    pub operator_approval: bool,
+    /// Collections that are not cw721 contracts
+    pub not_cw721: Vec<String>,
+    /// (collection, token id) pairs listed by `Tokens` for the swap contract
+    pub held: Vec<(String, String)>,
+}
+
+impl Querier for Cw721Querier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let (contract_addr, msg) = match from_slice(bin_request).unwrap() {
+            QueryRequest::<Empty>::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                (contract_addr, msg)
+            }
+            _ => panic!("Unexpected query"),
+        };
+        if self.not_cw721.contains(&contract_addr) {
+            return SystemResult::Ok(ContractResult::Err(String::from("Unknown query")));
+        }
+        let contract_approval = Approval {
+            spender: mock_env().contract.address.to_string(),
+            expires: Expiration::Never {},
+        };
+        let response: Binary = match from_slice(&msg).unwrap() {
+            Cw721QueryMsg::OwnerOf { token_id, .. } => {
+                let owner = match (self.owners)(&contract_addr, &token_id) {
+                    Some(owner) => owner,
+                    None => {
+                        return SystemResult::Ok(ContractResult::Err(String::from(
+                            "Token not found",
+                        )))
+                    }
+                };
+                let approvals = match self.token_approval {
+                    true => vec![contract_approval],
+                    false => vec![],
+                };
+                to_binary(&OwnerOfResponse { owner, approvals }).unwrap()
+            }
+            Cw721QueryMsg::ApprovedForAll { .. } => {
+                let operators = match self.operator_approval {
+                    true => vec![contract_approval],
+                    false => vec![],
+                };
+                to_binary(&ApprovedForAllResponse { operators }).unwrap()
+            }
+            Cw721QueryMsg::NumTokens {} => to_binary(&NumTokensResponse { count: 1 }).unwrap(),
+            Cw721QueryMsg::Tokens {
+                start_after, limit, ..
+            } => {
+                let mut tokens: Vec<String> = self
+                    .held
+                    .iter()
+                    .filter(|(collection, _)| *collection == contract_addr)
+                    .map(|(_, token_id)| token_id.clone())
+                    .filter(|token_id| Some(token_id) > start_after.as_ref())
+                    .collect();
+                tokens.sort();
+                tokens.truncate(limit.unwrap_or(10) as usize);
+                to_binary(&TokensResponse { tokens }).unwrap()
+            }
+            _ => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: String::from("cw721 metadata"),
+                })
+            }
+        };
+        SystemResult::Ok(ContractResult::Ok(response))
+    }
+}
+
+/// Tokens of "goochi-goochi" belong to "responder", any other token belongs to "swapper"
+pub fn mock_dependencies() -> Cw721Deps {
+    mock_dependencies_with_owners(|collection, _| match collection {
+        "goochi-goochi" => Some(String::from("responder")),
+        _ => Some(String::from("swapper")),
+    })
+}
+
+pub fn mock_dependencies_with_owners(
+    owners: impl Fn(&str, &str) -> Option<String> + 'static,
+) -> Cw721Deps {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: Cw721Querier {
+            owners: Box::new(owners),
+            token_approval: true,
+            operator_approval: false,
+            not_cw721: vec![],
+            held: vec![],
+        },
+    }
+}
+
+pub fn signing_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32]).unwrap()
+}
+
+pub fn pubkey(key: &SigningKey) -> Binary {
+    Binary::from(key.verifying_key().to_bytes().as_slice())
+}
+
+/// Account address of the key, as the chain derives it
+pub fn address(key: &SigningKey) -> String {
+    let hash = Ripemd160::digest(&Sha256::digest(&pubkey(key)));
+    bech32::encode("terra", hash.to_base32(), Variant::Bech32).unwrap()
+}
+
+pub fn sign(key: &SigningKey, offer: &SignedOffer) -> Binary {
+    let signature: Signature = key.sign(&to_vec(offer).unwrap());
+    Binary::from(signature.as_ref())
+}
@@ -1,19 +1,23 @@
-use cosmwasm_std::{Addr, StdError};
+mod common;
+
+use cosmwasm_std::{Addr, StdError, Uint64};
 
 use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
 use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{coins, from_binary};
     #[allow(unused_imports)]
     use pretty_assertions::{assert_eq, assert_ne};
 
     #[test]
     fn proper_initialization() -> Result<(), String> {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
         let info = mock_info("creator", &coins(1000, "earth"));
 
@@ -25,20 +29,22 @@ mod tests {
     #[test]
     fn swap_validation() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
         let creator_info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), creator_info, msg);
 
         let get_swap_msg = QueryMsg::GetSwap {
-            swap_id: String::from("1"),
+            swap_id: Uint64::new(1),
         };
 
         // Initiate Swap
-        let swapper_info = mock_info("swapper", &coins(2, "token"));
+        let swapper_info = mock_info("swapper", &[]);
         let create_swap_msg = ExecuteMsg::InitiateSwap {
             collection: String::from("gp_collection"),
             token_id: String::from("123"),
+            expires: None,
+            custody: None,
         };
         execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
 
@@ -59,13 +65,13 @@ mod tests {
     #[test]
     fn create_swap() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
         let creator_info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), creator_info, msg);
 
         let get_swap_msg = QueryMsg::GetSwap {
-            swap_id: String::from("1"),
+            swap_id: Uint64::new(1),
         };
 
         // When Swap does not exists
@@ -81,10 +87,12 @@ mod tests {
         };
 
         // Initiate Swap
-        let swapper_info = mock_info("swapper", &coins(2, "token"));
+        let swapper_info = mock_info("swapper", &[]);
         let create_swap_msg = ExecuteMsg::InitiateSwap {
             collection: String::from("gp_collection"),
             token_id: String::from("123"),
+            expires: None,
+            custody: None,
         };
         execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
 
@@ -101,4 +109,32 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn cant_send_funds() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Funds would be stuck in the contract
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &coins(2, "token")),
+            create_swap_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NonPayable {}),
+        };
+
+        Ok(())
+    }
 }
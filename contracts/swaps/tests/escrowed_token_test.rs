@@ -0,0 +1,179 @@
+mod common;
+
+use cosmwasm_std::{Deps, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{EscrowedTokenResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TokenEscrow};
+use swaps::state::SwapRole;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, mock_dependencies_with_owners};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn escrow(deps: Deps, collection: &str, token_id: &str) -> Option<TokenEscrow> {
+        let escrowed_token_msg = QueryMsg::EscrowedToken {
+            collection: String::from(collection),
+            token_id: String::from(token_id),
+        };
+        let response: EscrowedTokenResponse =
+            from_binary(&query(deps, mock_env(), escrowed_token_msg).unwrap()).unwrap();
+        response.escrow
+    }
+
+    #[test]
+    fn escrowed_token_lookup() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        assert_eq!(escrow(deps.as_ref(), "gp_collection", "123"), None);
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        assert_eq!(
+            escrow(deps.as_ref(), "gp_collection", "123"),
+            Some(TokenEscrow {
+                swap_id: Uint64::new(1),
+                role: SwapRole::Listing,
+            })
+        );
+
+        // Reply to Swap
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        assert_eq!(
+            escrow(deps.as_ref(), "goochi-goochi", "abc"),
+            Some(TokenEscrow {
+                swap_id: Uint64::new(1),
+                role: SwapRole::Offer,
+            })
+        );
+
+        // Finished Swaps don't hold tokens anymore
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), swapper_info, finalize_swap_msg).unwrap();
+
+        assert_eq!(escrow(deps.as_ref(), "gp_collection", "123"), None);
+        assert_eq!(escrow(deps.as_ref(), "goochi-goochi", "abc"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cant_escrow_token_twice() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies_with_owners(|_, token_id| match token_id {
+            "456" => Some(String::from("other")),
+            _ => Some(String::from("swapper")),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg.clone(),
+        )
+        .unwrap();
+
+        // Listing the same token again
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::TokenAlreadyEscrowed { swap_id: 1 }),
+        };
+
+        // Offering a listed token
+        let create_other_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("456"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            create_other_swap_msg,
+        )
+        .unwrap();
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(2),
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            swap_reply_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::TokenAlreadyEscrowed { swap_id: 1 }),
+        };
+
+        // Token can be listed again once the Swap is cancelled
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(1),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            cancel_swap_msg,
+        )
+        .unwrap();
+        execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use cosmwasm_std::{Event, Response, Uint64};
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate};
+use swaps::msg::{ExecuteMsg, InstantiateMsg};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::Env;
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    /// The schema indexers rely on, changing it is a breaking change
+    fn swap_event(name: &str, swap_id: &str, status: &str, rhs: Option<(&str, &str)>) -> Event {
+        let (rhs_owner, rhs_collection, rhs_token_id) = match rhs {
+            Some((owner, token_id)) => (owner, "goochi-goochi", token_id),
+            None => ("", "", ""),
+        };
+        Event::new(name)
+            .add_attribute("swap_id", swap_id)
+            .add_attribute("status", status)
+            .add_attribute("lhs_owner", "swapper")
+            .add_attribute("lhs_collection", "gp_collection")
+            .add_attribute("lhs_token_id", swap_id)
+            .add_attribute("rhs_owner", rhs_owner)
+            .add_attribute("rhs_collection", rhs_collection)
+            .add_attribute("rhs_token_id", rhs_token_id)
+    }
+
+    fn later_env(blocks: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        env
+    }
+
+    #[test]
+    fn lifecycle_events() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        let swapper_info = mock_info("swapper", &[]);
+        let responder_info = mock_info("responder", &[]);
+        let swap_id = |id: u64| Uint64::new(id);
+        let events = |res: Response| res.events;
+
+        // Initiate Swaps 1 to 6, Swap 5 expires in 10 blocks
+        for id in 1..=6 {
+            let expires = match id {
+                5 => Some(Expiration::AtHeight(mock_env().block.height + 10)),
+                _ => None,
+            };
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: id.to_string(),
+                expires,
+                custody: None,
+            };
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                swapper_info.clone(),
+                create_swap_msg,
+            )
+            .unwrap();
+            assert_eq!(
+                events(res),
+                vec![swap_event("swap_created", &id.to_string(), "open", None)]
+            );
+        }
+
+        // Offers on Swaps 1 to 5
+        for id in 1..=5 {
+            let swap_reply_msg = ExecuteMsg::SwapReply {
+                swap_id: swap_id(id),
+                collection: String::from("goochi-goochi"),
+                token_id: format!("abc{}", id),
+            };
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                responder_info.clone(),
+                swap_reply_msg,
+            )
+            .unwrap();
+            let token_id = format!("abc{}", id);
+            assert_eq!(
+                events(res),
+                vec![swap_event(
+                    "offer_made",
+                    &id.to_string(),
+                    "offered",
+                    Some(("responder", &token_id))
+                )]
+            );
+        }
+
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: swap_id(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            finalize_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![swap_event(
+                "swap_settled",
+                "1",
+                "completed",
+                Some(("responder", "abc1"))
+            )]
+        );
+
+        let reject_offer_msg = ExecuteMsg::RejectOffer {
+            swap_id: swap_id(2),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            reject_offer_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![swap_event(
+                "offer_rejected",
+                "2",
+                "open",
+                Some(("responder", "abc2"))
+            )]
+        );
+
+        let withdraw_offer_msg = ExecuteMsg::WithdrawOffer {
+            swap_id: swap_id(3),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info,
+            withdraw_offer_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![swap_event(
+                "offer_withdrawn",
+                "3",
+                "open",
+                Some(("responder", "abc3"))
+            )]
+        );
+
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: swap_id(4),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            cancel_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![swap_event(
+                "swap_cancelled",
+                "4",
+                "cancelled",
+                Some(("responder", "abc4"))
+            )]
+        );
+
+        let reclaim_msg = ExecuteMsg::ReclaimExpiredSwap {
+            swap_id: swap_id(5),
+        };
+        let res = execute(
+            deps.as_mut(),
+            later_env(10),
+            mock_info("anyone", &[]),
+            reclaim_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![swap_event(
+                "swap_expired",
+                "5",
+                "expired",
+                Some(("responder", "abc5"))
+            )]
+        );
+
+        // Refunds after shutdown are reported as cancellations
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            ExecuteMsg::Shutdown {},
+        )
+        .unwrap();
+        let refund_all_msg = ExecuteMsg::RefundAll { limit: None };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            refund_all_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            events(res),
+            vec![
+                swap_event("swap_cancelled", "2", "cancelled", None),
+                swap_event("swap_cancelled", "3", "cancelled", None),
+                swap_event("swap_cancelled", "6", "cancelled", None),
+            ]
+        );
+
+        Ok(())
+    }
+}
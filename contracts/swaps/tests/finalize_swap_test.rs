@@ -1,3 +1,7 @@
+mod common;
+
+use cosmwasm_std::Uint64;
+
 use swaps::contract::{execute, instantiate};
 use swaps::error::ContractError;
 use swaps::msg::{ExecuteMsg, InstantiateMsg};
@@ -5,22 +9,23 @@ use swaps::msg::{ExecuteMsg, InstantiateMsg};
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::mock_dependencies;
     use cosmwasm_std::coins;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::testing::{mock_env, mock_info};
     #[allow(unused_imports)]
     use pretty_assertions::{assert_eq, assert_ne};
 
     #[test]
     fn cant_finalize_not_existing_sawp() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
-        let creator_info = mock_info("creator", &coins(2, "token"));
+        let creator_info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg);
 
         // Try to finalize not existing Swap
         let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
-            swap_id: "123".to_string(),
+            swap_id: Uint64::new(123),
         };
         match execute(
             deps.as_mut(),
@@ -38,16 +43,18 @@ mod tests {
     #[test]
     fn cant_finalize_unresponded_sawp() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         let msg = InstantiateMsg {};
         let creator_info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), creator_info, msg);
 
         // Initiate Swap
-        let swapper_info = mock_info("swapper", &coins(2, "token"));
+        let swapper_info = mock_info("swapper", &[]);
         let create_swap_msg = ExecuteMsg::InitiateSwap {
             collection: String::from("gp_collection"),
             token_id: String::from("123"),
+            expires: None,
+            custody: None,
         };
         let swap_created = execute(
             deps.as_mut(),
@@ -59,13 +66,15 @@ mod tests {
         let swap_id = swap_created
             .attributes
             .iter()
-            .find(move |x| x.key == String::from("swap_id"))
+            .find(move |x| x.key == "swap_id")
             .unwrap()
             .value
-            .clone();
+            .parse()
+            .map(Uint64::new)
+            .unwrap();
 
         // Try to finalize not responded Swap
-        let finalize_swap_msg = ExecuteMsg::FinalizeSwap { swap_id: swap_id };
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap { swap_id };
         match execute(
             deps.as_mut(),
             mock_env(),
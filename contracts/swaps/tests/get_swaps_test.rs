@@ -0,0 +1,79 @@
+mod common;
+
+use cosmwasm_std::{StdError, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, GetSwapsResponse, InstantiateMsg, QueryMsg};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    #[test]
+    fn get_swaps_by_ids() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swaps 1 and 2
+        for token_id in 1..=2 {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: token_id.to_string(),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+
+        // Missing ids don't fail the query, order is preserved
+        let get_swaps_msg = QueryMsg::GetSwaps {
+            ids: vec![Uint64::new(2), Uint64::new(7), Uint64::new(1)],
+        };
+        let response: GetSwapsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), get_swaps_msg).unwrap()).unwrap();
+        let found: Vec<(u64, Option<String>)> = response
+            .swaps
+            .into_iter()
+            .map(|lookup| {
+                let token_id = lookup.swap.map(|swap| swap.lhs.token_id);
+                (lookup.swap_id.u64(), token_id)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, Some(String::from("2"))),
+                (7, None),
+                (1, Some(String::from("1"))),
+            ]
+        );
+
+        // Too many ids
+        let too_many_msg = QueryMsg::GetSwaps {
+            ids: (1..=31).map(Uint64::new).collect(),
+        };
+        match query(deps.as_ref(), mock_env(), too_many_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                StdError::generic_err("Too many ids, at most 30 swaps can be fetched at once")
+            ),
+        };
+
+        Ok(())
+    }
+}
@@ -1,27 +1,37 @@
+mod common;
+
+use cosmwasm_std::Uint64;
+
 use swaps::contract::{execute, instantiate};
 use swaps::msg::{ExecuteMsg, InstantiateMsg};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::mock_dependencies_with_owners;
     use cosmwasm_std::coins;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::testing::{mock_env, mock_info};
     #[allow(unused_imports)]
     use pretty_assertions::{assert_eq, assert_ne};
 
     #[test]
     fn swap_happy_path() -> Result<(), String> {
         // Initialization
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies_with_owners(|collection, _| match collection {
+            "goochi-goochi" => Some(String::from("responder")),
+            _ => Some(String::from("creator")),
+        });
         let msg = InstantiateMsg {};
         let owner = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), owner, msg);
 
         // Create Swap
-        let creator_info = mock_info("creator", &coins(2, "token"));
+        let creator_info = mock_info("creator", &[]);
         let initiate_swap_msg = ExecuteMsg::InitiateSwap {
             collection: String::from("gp_collection"),
             token_id: String::from("123"),
+            expires: None,
+            custody: None,
         };
         let swap_created = execute(
             deps.as_mut(),
@@ -34,22 +44,24 @@ mod tests {
         let swap_id = swap_created
             .attributes
             .iter()
-            .find(move |x| x.key == String::from("swap_id"))
+            .find(move |x| x.key == "swap_id")
             .unwrap()
             .value
-            .clone();
+            .parse()
+            .map(Uint64::new)
+            .unwrap();
 
         // Reply to Swap
-        let responder_info = mock_info("responder", &coins(2, "token"));
+        let responder_info = mock_info("responder", &[]);
         let swap_reply_msg = ExecuteMsg::SwapReply {
-            swap_id: swap_id.clone(),
+            swap_id,
             collection: String::from("goochi-goochi"),
             token_id: String::from("abc"),
         };
         execute(deps.as_mut(), mock_env(), responder_info, swap_reply_msg).unwrap();
 
         // Finalize Swap
-        let finalize_swap_msg = ExecuteMsg::FinalizeSwap { swap_id: swap_id };
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap { swap_id };
         execute(
             deps.as_mut(),
             mock_env(),
@@ -0,0 +1,250 @@
+mod common;
+
+use cosmwasm_std::{to_binary, Binary, CosmosMsg, Deps, Env, SubMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+use cw721::Expiration;
+use sha2::{Digest, Sha256};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, HtlcResponse, InstantiateMsg, PreimageResponse, QueryMsg};
+use swaps::state::HtlcStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{attr, coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    const PREIMAGE: &[u8] = b"the secret both legs hinge on";
+
+    fn hashlock() -> Binary {
+        Binary::from(Sha256::digest(PREIMAGE).as_slice())
+    }
+
+    fn env_at_height(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    /// Locks gp_collection 123 of the swapper for the responder until 10 blocks from now
+    fn lock(deps: &mut Cw721Deps) -> Vec<SubMsg> {
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let lock_htlc_msg = ExecuteMsg::LockHtlc {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            recipient: String::from("responder"),
+            hashlock: hashlock(),
+            expires: Expiration::AtHeight(mock_env().block.height + 10),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            lock_htlc_msg,
+        )
+        .unwrap()
+        .messages
+    }
+
+    fn claim(deps: &mut Cw721Deps, env: Env, preimage: &[u8]) -> Result<SubMsg, ContractError> {
+        let claim_htlc_msg = ExecuteMsg::ClaimHtlc {
+            htlc_id: Uint64::new(1),
+            preimage: Binary::from(preimage),
+        };
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), claim_htlc_msg)
+            .map(|res| res.messages[0].clone())
+    }
+
+    fn refund(deps: &mut Cw721Deps, env: Env) -> Result<SubMsg, ContractError> {
+        let refund_htlc_msg = ExecuteMsg::RefundHtlc {
+            htlc_id: Uint64::new(1),
+        };
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            refund_htlc_msg,
+        )
+        .map(|res| res.messages[0].clone())
+    }
+
+    fn get_htlc(deps: Deps) -> HtlcResponse {
+        let htlc_msg = QueryMsg::Htlc {
+            htlc_id: Uint64::new(1),
+        };
+        from_binary(&query(deps, mock_env(), htlc_msg).unwrap()).unwrap()
+    }
+
+    fn get_preimage(deps: Deps) -> Option<Binary> {
+        let preimage_msg = QueryMsg::Preimage {
+            hashlock: hashlock(),
+        };
+        let response: PreimageResponse =
+            from_binary(&query(deps, mock_env(), preimage_msg).unwrap()).unwrap();
+        response.preimage
+    }
+
+    fn transfer(recipient: &str) -> SubMsg {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("gp_collection"),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: String::from(recipient),
+                token_id: String::from("123"),
+            })
+            .unwrap(),
+        }))
+    }
+
+    #[test]
+    fn claim_with_preimage() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+
+        let messages = lock(&mut deps);
+        assert_eq!(messages, vec![transfer("cosmos2contract")]);
+        assert_eq!(get_htlc(deps.as_ref()).status, HtlcStatus::Locked);
+
+        match claim(&mut deps, mock_env(), b"a guess") {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::InvalidPreimage {}),
+        };
+
+        // Anyone holding the preimage releases the NFT to the recipient
+        let claim_htlc_msg = ExecuteMsg::ClaimHtlc {
+            htlc_id: Uint64::new(1),
+            preimage: Binary::from(PREIMAGE),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            claim_htlc_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![transfer("responder")]);
+        assert_eq!(res.events[0].ty, "htlc_claimed");
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("preimage", Binary::from(PREIMAGE).to_base64())));
+
+        // The other chain's leg can be completed with the published preimage
+        let htlc = get_htlc(deps.as_ref());
+        assert_eq!(htlc.status, HtlcStatus::Claimed);
+        assert_eq!(htlc.preimage, Some(Binary::from(PREIMAGE)));
+        assert_eq!(get_preimage(deps.as_ref()), Some(Binary::from(PREIMAGE)));
+
+        match refund(&mut deps, env_at_height(mock_env().block.height + 10)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::HtlcNotLocked {
+                    status: HtlcStatus::Claimed,
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn refund_after_timeout() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        lock(&mut deps);
+
+        match refund(&mut deps, mock_env()) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::HtlcNotExpired {}),
+        };
+
+        // Too late to claim, the NFT goes back to the depositor
+        let expired = env_at_height(mock_env().block.height + 10);
+        match claim(&mut deps, expired.clone(), PREIMAGE) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::HtlcExpired {}),
+        };
+        assert_eq!(refund(&mut deps, expired).unwrap(), transfer("swapper"));
+        assert_eq!(get_htlc(deps.as_ref()).status, HtlcStatus::Refunded);
+        assert_eq!(get_preimage(deps.as_ref()), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_locks() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let cases = [
+            (
+                Binary::from(&[0u8; 20][..]),
+                Expiration::AtHeight(mock_env().block.height + 10),
+                ContractError::InvalidHashlock {},
+            ),
+            (
+                hashlock(),
+                Expiration::Never {},
+                ContractError::HtlcTimelockRequired {},
+            ),
+            (
+                hashlock(),
+                Expiration::AtHeight(mock_env().block.height),
+                ContractError::HtlcExpired {},
+            ),
+        ];
+        for (hashlock, expires, expected) in &cases {
+            let lock_htlc_msg = ExecuteMsg::LockHtlc {
+                collection: String::from("gp_collection"),
+                token_id: String::from("123"),
+                recipient: String::from("responder"),
+                hashlock: hashlock.clone(),
+                expires: *expires,
+            };
+            match execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                lock_htlc_msg,
+            ) {
+                Ok(_) => panic!("Error expected"),
+                Err(err) => assert_eq!(err, *expected),
+            };
+        }
+
+        let lock_htlc_msg = ExecuteMsg::LockHtlc {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            recipient: String::from("r"),
+            hashlock: hashlock(),
+            expires: Expiration::AtHeight(mock_env().block.height + 10),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            lock_htlc_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidAccountAddress {
+                    address: String::from("r"),
+                }
+            ),
+        };
+
+        Ok(())
+    }
+}
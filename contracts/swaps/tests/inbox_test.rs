@@ -0,0 +1,142 @@
+mod common;
+
+use cosmwasm_std::{Deps, Env, Uint64};
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, InboxResponse, InstantiateMsg, QueryMsg};
+use swaps::state::SwapAction;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies_with_owners;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn inbox(
+        deps: Deps,
+        env: Env,
+        address: &str,
+        limit: Option<u32>,
+    ) -> Vec<(u64, Vec<SwapAction>)> {
+        let inbox_msg = QueryMsg::Inbox {
+            address: String::from(address),
+            start_after: None,
+            limit,
+        };
+        let response: InboxResponse = from_binary(&query(deps, env, inbox_msg).unwrap()).unwrap();
+        response
+            .entries
+            .into_iter()
+            .map(|entry| (entry.swap.swap_id.u64(), entry.actions))
+            .collect()
+    }
+
+    fn later_env(blocks: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        env
+    }
+
+    #[test]
+    fn inbox_lists_pending_actions() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies_with_owners(|collection, _| match collection {
+            "goochi-goochi" => Some(String::from("bob")),
+            _ => Some(String::from("alice")),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Alice lists three NFTs, the last one expires in 10 blocks
+        let expirations = vec![
+            None,
+            None,
+            Some(Expiration::AtHeight(mock_env().block.height + 10)),
+        ];
+        for (token_id, expires) in expirations.into_iter().enumerate() {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: token_id.to_string(),
+                expires,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+
+        // Nothing to do yet
+        assert_eq!(inbox(deps.as_ref(), mock_env(), "alice", None), vec![]);
+
+        // Bob offers on Swaps 2 and 3
+        for swap_id in 2..=3 {
+            let swap_reply_msg = ExecuteMsg::SwapReply {
+                swap_id: Uint64::new(swap_id),
+                collection: String::from("goochi-goochi"),
+                token_id: swap_id.to_string(),
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob", &[]),
+                swap_reply_msg,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            inbox(deps.as_ref(), mock_env(), "alice", None),
+            vec![
+                (2, vec![SwapAction::Accept, SwapAction::Reject]),
+                (3, vec![SwapAction::Accept, SwapAction::Reject]),
+            ]
+        );
+        assert_eq!(inbox(deps.as_ref(), mock_env(), "bob", None), vec![]);
+
+        // After expiration both parties can reclaim Swap 3
+        assert_eq!(
+            inbox(deps.as_ref(), later_env(10), "alice", None),
+            vec![
+                (2, vec![SwapAction::Accept, SwapAction::Reject]),
+                (3, vec![SwapAction::Expire]),
+            ]
+        );
+        assert_eq!(
+            inbox(deps.as_ref(), later_env(10), "bob", None),
+            vec![(3, vec![SwapAction::Expire])]
+        );
+
+        // Limit applies to the merged page
+        assert_eq!(
+            inbox(deps.as_ref(), later_env(10), "alice", Some(1)),
+            vec![(2, vec![SwapAction::Accept, SwapAction::Reject])]
+        );
+
+        // Rejected offer leaves the inbox
+        let reject_offer_msg = ExecuteMsg::RejectOffer {
+            swap_id: Uint64::new(2),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            reject_offer_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            inbox(deps.as_ref(), mock_env(), "alice", None),
+            vec![(3, vec![SwapAction::Accept, SwapAction::Reject])]
+        );
+
+        Ok(())
+    }
+}
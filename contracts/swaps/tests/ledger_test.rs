@@ -0,0 +1,172 @@
+mod common;
+
+use cosmwasm_std::{coins, Deps, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReconcileResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn reconcile(
+        deps: Deps,
+        collection: &str,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> ReconcileResponse {
+        let reconcile_msg = QueryMsg::Reconcile {
+            collection: String::from(collection),
+            start_after: start_after.map(String::from),
+            limit,
+        };
+        from_binary(&query(deps, mock_env(), reconcile_msg).unwrap()).unwrap()
+    }
+
+    fn held(collection: &str, token_id: &str) -> (String, String) {
+        (String::from(collection), String::from(token_id))
+    }
+
+    #[test]
+    fn reconcile_escrowed_nfts() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        for token_id in &["1", "2"] {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: String::from(*token_id),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        // Token 2 never arrived, token 9 was sent to the contract directly
+        deps.querier.held = vec![
+            held("gp_collection", "1"),
+            held("gp_collection", "9"),
+            held("goochi-goochi", "abc"),
+        ];
+        assert_eq!(
+            reconcile(deps.as_ref(), "gp_collection", None, None),
+            ReconcileResponse {
+                orphaned: vec![String::from("9")],
+                missing: vec![String::from("2")],
+                matched: 1,
+                next: None,
+            }
+        );
+
+        // Pages follow token ids across both sides
+        let page = reconcile(deps.as_ref(), "gp_collection", None, Some(1));
+        assert_eq!(page.matched, 1);
+        assert_eq!(page.next, Some(String::from("1")));
+        let page = reconcile(deps.as_ref(), "gp_collection", Some("1"), Some(1));
+        assert_eq!(page.missing, vec![String::from("2")]);
+        assert_eq!(page.orphaned, Vec::<String>::new());
+        assert_eq!(page.next, Some(String::from("2")));
+
+        // Settled NFTs leave the ledger
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            finalize_swap_msg,
+        )
+        .unwrap();
+        deps.querier.held = vec![];
+        assert_eq!(
+            reconcile(deps.as_ref(), "goochi-goochi", None, None),
+            ReconcileResponse {
+                orphaned: vec![],
+                missing: vec![],
+                matched: 0,
+                next: None,
+            }
+        );
+        assert_eq!(
+            reconcile(deps.as_ref(), "gp_collection", None, None).missing,
+            vec![String::from("2")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reconcile_pages_beyond_both_sides() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        for token_id in &["c", "d"] {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: String::from(*token_id),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+        deps.querier.held = vec![held("gp_collection", "a"), held("gp_collection", "b")];
+
+        // Both sides fit in the page, but not their merge
+        assert_eq!(
+            reconcile(deps.as_ref(), "gp_collection", None, Some(3)),
+            ReconcileResponse {
+                orphaned: vec![String::from("a"), String::from("b")],
+                missing: vec![String::from("c")],
+                matched: 0,
+                next: Some(String::from("c")),
+            }
+        );
+        assert_eq!(
+            reconcile(deps.as_ref(), "gp_collection", Some("c"), Some(3)),
+            ReconcileResponse {
+                orphaned: vec![],
+                missing: vec![String::from("d")],
+                matched: 0,
+                next: None,
+            }
+        );
+
+        Ok(())
+    }
+}
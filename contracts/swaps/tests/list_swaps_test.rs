@@ -0,0 +1,145 @@
+mod common;
+
+use cosmwasm_std::{Deps, Uint64};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, InstantiateMsg, ListSwapsResponse, QueryMsg};
+use swaps::state::SwapStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies_with_owners;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn list_swaps(deps: Deps, msg: QueryMsg) -> (Vec<u64>, Option<Uint64>) {
+        let response: ListSwapsResponse =
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        let ids = response
+            .swaps
+            .iter()
+            .map(|swap| swap.swap_id.u64())
+            .collect();
+        (ids, response.next)
+    }
+
+    #[test]
+    fn list_swaps_with_filters() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies_with_owners(|collection, _| match collection {
+            "goochi-goochi" => Some(String::from("bob")),
+            _ => Some(String::from("alice")),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swaps 1 to 12, alternating owners and collections
+        for token_id in 1..=12 {
+            let (owner, collection) = match token_id % 2 {
+                0 => ("alice", "gp_collection"),
+                _ => ("bob", "goochi-goochi"),
+            };
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from(collection),
+                token_id: token_id.to_string(),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+
+        // Cancel Swap 2
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(2),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            cancel_swap_msg,
+        )
+        .unwrap();
+
+        // Default limit
+        let all_msg = QueryMsg::ListSwaps {
+            start_after: None,
+            limit: None,
+            status: None,
+            owner: None,
+            collection: None,
+        };
+        let (ids, next) = list_swaps(deps.as_ref(), all_msg);
+        assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+        assert_eq!(next, Some(Uint64::new(10)));
+
+        // Next page
+        let next_page_msg = QueryMsg::ListSwaps {
+            start_after: next,
+            limit: None,
+            status: None,
+            owner: None,
+            collection: None,
+        };
+        let (ids, next) = list_swaps(deps.as_ref(), next_page_msg);
+        assert_eq!(ids, vec![11, 12]);
+        assert_eq!(next, None);
+
+        // Open Swaps of alice
+        let owner_msg = QueryMsg::ListSwaps {
+            start_after: None,
+            limit: Some(3),
+            status: Some(SwapStatus::Open),
+            owner: Some(String::from("alice")),
+            collection: None,
+        };
+        let (ids, next) = list_swaps(deps.as_ref(), owner_msg);
+        assert_eq!(ids, vec![4, 6, 8]);
+        assert_eq!(next, Some(Uint64::new(8)));
+
+        // Swaps in collection
+        let collection_msg = QueryMsg::ListSwaps {
+            start_after: Some(Uint64::new(7)),
+            limit: None,
+            status: None,
+            owner: None,
+            collection: Some(String::from("goochi-goochi")),
+        };
+        let (ids, next) = list_swaps(deps.as_ref(), collection_msg);
+        assert_eq!(ids, vec![9, 11]);
+        assert_eq!(next, None);
+
+        // Owner and collection that do not match
+        let mismatch_msg = QueryMsg::ListSwaps {
+            start_after: None,
+            limit: None,
+            status: None,
+            owner: Some(String::from("alice")),
+            collection: Some(String::from("goochi-goochi")),
+        };
+        let (ids, _) = list_swaps(deps.as_ref(), mismatch_msg);
+        assert_eq!(ids, Vec::<u64>::new());
+
+        // Cancelled Swaps
+        let cancelled_msg = QueryMsg::ListSwaps {
+            start_after: None,
+            limit: None,
+            status: Some(SwapStatus::Cancelled),
+            owner: None,
+            collection: None,
+        };
+        let (ids, _) = list_swaps(deps.as_ref(), cancelled_msg);
+        assert_eq!(ids, vec![2]);
+
+        Ok(())
+    }
+}
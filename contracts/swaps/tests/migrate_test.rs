@@ -0,0 +1,166 @@
+use cosmwasm_std::{Addr, Order, Storage, Uint64};
+use cw2::{get_contract_version, set_contract_version};
+
+use swaps::contract::{migrate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ConfigResponse, MigrateMsg, QueryMsg, StatsResponse, SwapResponse};
+use swaps::state::{swaps, CollectionMode, PayoutMode, SwapStatus, LEDGER_NFTS};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    /// State as written by the 0.1.0 release, with open Swaps under the given ids
+    fn store_v0_1_state(storage: &mut dyn Storage, swap_ids: &[&str]) {
+        set_contract_version(storage, "crates.io:swaps", "0.1.0").unwrap();
+        storage.set(b"config", br#"{"next_swap_id":11}"#);
+
+        for swap_id in swap_ids {
+            let mut swap_key = vec![0, 5];
+            swap_key.extend_from_slice(b"swaps");
+            swap_key.extend_from_slice(swap_id.as_bytes());
+            let swap = format!(
+                r#"{{"lhs":{{"owner":"swapper","collection":"gp_collection","token_id":"{}"}},"rhs":null}}"#,
+                swap_id
+            );
+            storage.set(&swap_key, swap.as_bytes());
+        }
+    }
+
+    #[test]
+    fn migrate_from_v0_1() -> Result<(), String> {
+        let mut deps = mock_dependencies(&[]);
+        store_v0_1_state(&mut deps.storage, &["1", "2", "10"]);
+
+        let migrate_msg = MigrateMsg {
+            admin: Some(String::from("admin")),
+        };
+        migrate(deps.as_mut(), mock_env(), migrate_msg).unwrap();
+
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        let config_response = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: ConfigResponse = from_binary(&config_response).unwrap();
+        assert_eq!(
+            config,
+            ConfigResponse {
+                admin: Addr::unchecked("admin"),
+                shutdown: false,
+                collection_mode: CollectionMode::Open,
+                payout_mode: PayoutMode::Push,
+            }
+        );
+
+        // Open Swaps survive the migration under numeric ids
+        for &swap_id in &[1, 2, 10] {
+            let get_swap_msg = QueryMsg::GetSwap {
+                swap_id: Uint64::new(swap_id),
+            };
+            let get_swap_response = query(deps.as_ref(), mock_env(), get_swap_msg).unwrap();
+            let swap_response: SwapResponse = from_binary(&get_swap_response).unwrap();
+            assert_eq!(swap_response.lhs.owner, Addr::unchecked("swapper"));
+            assert_eq!(swap_response.lhs.token_id, swap_id.to_string());
+            assert_eq!(swap_response.status, SwapStatus::Open);
+        }
+
+        // Migrated Swaps are indexed
+        let owned = swaps()
+            .idx
+            .lhs_owner
+            .prefix(b"swapper".to_vec())
+            .range(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(owned, 3);
+
+        // Stats are counted from the migrated Swaps
+        let stats_response = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let stats: StatsResponse = from_binary(&stats_response).unwrap();
+        assert_eq!(stats.open, 3);
+        assert_eq!(stats.escrowed_nfts, 3);
+
+        // Their escrowed NFTs are recorded in the ledger
+        let recorded = LEDGER_NFTS
+            .prefix(&Addr::unchecked("gp_collection"))
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .collect::<Vec<_>>();
+        assert_eq!(recorded, vec![b"1".to_vec(), b"10".to_vec(), b"2".to_vec()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_v0_1_requires_admin() -> Result<(), String> {
+        let mut deps = mock_dependencies(&[]);
+        store_v0_1_state(&mut deps.storage, &["1"]);
+
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::MigrationAdminRequired {}),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_v0_1_rejects_malformed_swap_id() -> Result<(), String> {
+        let mut deps = mock_dependencies(&[]);
+        store_v0_1_state(&mut deps.storage, &["1", "01"]);
+
+        let migrate_msg = MigrateMsg {
+            admin: Some(String::from("admin")),
+        };
+        match migrate(deps.as_mut(), mock_env(), migrate_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidSwapId {
+                    swap_id: String::from("01")
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn cant_downgrade() -> Result<(), String> {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, "crates.io:swaps", "99.0.0").unwrap();
+
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::CannotDowngrade {
+                    previous_version: String::from("99.0.0"),
+                    new_version: String::from(env!("CARGO_PKG_VERSION")),
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn cant_migrate_other_contract() -> Result<(), String> {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.1.0").unwrap();
+
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::CannotMigrate {
+                    previous_contract: String::from("crates.io:cw20-base"),
+                }
+            ),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+mod common;
+
+use cosmwasm_std::{attr, to_binary, CosmosMsg, Deps, Response, SubMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{
+    EscrowedTokenResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StatsResponse, SwapResponse,
+};
+use swaps::state::{Custody, SwapStatus};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn get_swap(deps: Deps) -> SwapResponse {
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        from_binary(&query(deps, mock_env(), get_swap_msg).unwrap()).unwrap()
+    }
+
+    fn transfer(collection: &str, recipient: &str, token_id: &str) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: collection.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            })
+            .unwrap(),
+        })
+    }
+
+    /// Lists and offers under approval custody, then finalizes
+    fn swap_with_approvals(
+        deps: &mut Cw721Deps,
+        before_finalize: impl Fn(&mut Cw721Deps),
+    ) -> Response {
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: Some(Custody::Approval),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.offered, 1);
+        assert_eq!(stats.escrowed_nfts, 0);
+
+        before_finalize(deps);
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            finalize_swap_msg,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn settle_from_owners_wallets() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+
+        let res = swap_with_approvals(&mut deps, |_| ());
+        let transfers: Vec<WasmMsg> = res
+            .messages
+            .into_iter()
+            .map(|SubMsg { msg, .. }| match msg {
+                CosmosMsg::Wasm(msg) => msg,
+                _ => panic!("Unexpected message"),
+            })
+            .collect();
+        assert_eq!(
+            transfers,
+            vec![
+                WasmMsg::Execute {
+                    contract_addr: String::from("gp_collection"),
+                    funds: vec![],
+                    msg: to_binary(&TransferNft {
+                        recipient: String::from("responder"),
+                        token_id: String::from("123"),
+                    })
+                    .unwrap(),
+                },
+                WasmMsg::Execute {
+                    contract_addr: String::from("goochi-goochi"),
+                    funds: vec![],
+                    msg: to_binary(&TransferNft {
+                        recipient: String::from("swapper"),
+                        token_id: String::from("abc"),
+                    })
+                    .unwrap(),
+                },
+            ]
+        );
+        assert_eq!(get_swap(deps.as_ref()).status, SwapStatus::Completed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_transfer_reverts_settlement() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+
+        // Neither transfer replies, so if one of them fails the whole acceptance reverts and
+        // the other NFT can't be delivered alone
+        let res = swap_with_approvals(&mut deps, |_| ());
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(transfer("gp_collection", "responder", "123")),
+                SubMsg::new(transfer("goochi-goochi", "swapper", "abc")),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn broken_when_nft_moved() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+
+        // The listed NFT was sold in the meantime
+        let res = swap_with_approvals(&mut deps, |deps| {
+            deps.querier.owners = Box::new(|collection, _| match collection {
+                "goochi-goochi" => Some(String::from("responder")),
+                _ => Some(String::from("buyer")),
+            })
+        });
+        assert_eq!(res.messages, vec![]);
+        assert!(res.attributes.contains(&attr("reason", "NotTokenOwner")));
+        assert_eq!(res.events[0].ty, "swap_broken");
+
+        let swap = get_swap(deps.as_ref());
+        assert_eq!(swap.status, SwapStatus::Broken);
+        assert_eq!(swap.custody, Custody::Approval);
+
+        Ok(())
+    }
+
+    #[test]
+    fn broken_when_approval_revoked() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+
+        let res = swap_with_approvals(&mut deps, |deps| deps.querier.token_approval = false);
+        assert_eq!(res.messages, vec![]);
+        assert!(res
+            .attributes
+            .contains(&attr("reason", "ContractNotApproved")));
+        assert_eq!(get_swap(deps.as_ref()).status, SwapStatus::Broken);
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.broken, 1);
+        assert_eq!(stats.completed, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn approved_nft_stays_free() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: Some(Custody::Approval),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        // The contract doesn't hold the listed NFT
+        let escrowed_token_msg = QueryMsg::EscrowedToken {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+        };
+        let response: EscrowedTokenResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), escrowed_token_msg).unwrap()).unwrap();
+        assert_eq!(response.escrow, None);
+
+        // Its buyer can list it while the old listing is still open
+        deps.querier.owners = Box::new(|collection, _| match collection {
+            "goochi-goochi" => Some(String::from("responder")),
+            _ => Some(String::from("buyer")),
+        });
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(get_swap(deps.as_ref()).status, SwapStatus::Open);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use cosmwasm_std::{Deps, Uint64};
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{
+    ExecuteMsg, InstantiateMsg, NonceStatus, NonceStatusResponse, NoncesResponse, OfferedNft,
+    QueryMsg, SignedOffer, WantedNft,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        address, mock_dependencies_with_owners, pubkey, sign, signing_key, Cw721Deps,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    use k256::ecdsa::SigningKey;
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn execute_offer(
+        deps: &mut Cw721Deps,
+        key: &SigningKey,
+        nonce: u64,
+    ) -> Result<(), ContractError> {
+        let offer = SignedOffer {
+            lister: address(key),
+            give: OfferedNft {
+                collection: String::from("gp_collection"),
+                token_id: String::from("123"),
+            },
+            want: WantedNft {
+                collection: String::from("goochi-goochi"),
+                token_id: None,
+            },
+            expires: Expiration::Never {},
+            nonce: Uint64::new(nonce),
+            contract: mock_env().contract.address.to_string(),
+        };
+        let execute_signed_offer_msg = ExecuteMsg::ExecuteSignedOffer {
+            signature: sign(key, &offer),
+            pubkey: pubkey(key),
+            offer,
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            execute_signed_offer_msg,
+        )
+        .map(|_| ())
+    }
+
+    fn nonces(deps: Deps, address: &str) -> NoncesResponse {
+        let nonces_msg = QueryMsg::Nonces {
+            address: String::from(address),
+            start_after: None,
+            limit: None,
+        };
+        from_binary(&query(deps, mock_env(), nonces_msg).unwrap()).unwrap()
+    }
+
+    fn nonce_status(deps: Deps, address: &str, nonce: u64) -> NonceStatus {
+        let nonce_status_msg = QueryMsg::NonceStatus {
+            address: String::from(address),
+            nonce: Uint64::new(nonce),
+        };
+        let response: NonceStatusResponse =
+            from_binary(&query(deps, mock_env(), nonce_status_msg).unwrap()).unwrap();
+        response.status
+    }
+
+    #[test]
+    fn nonces_are_single_use() -> Result<(), String> {
+        // Initialization
+        let key = signing_key(1);
+        let lister = address(&key);
+        let owner = lister.clone();
+        let mut deps = mock_dependencies_with_owners(move |collection, _| match collection {
+            "goochi-goochi" => Some(String::from("responder")),
+            _ => Some(owner.clone()),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // The same signature can't be executed twice
+        execute_offer(&mut deps, &key, 1).unwrap();
+        match execute_offer(&mut deps, &key, 1) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NonceUsed { nonce: 1 }),
+        };
+
+        // Revoked before anyone executed it
+        let cancel_nonces_msg = ExecuteMsg::CancelNonces {
+            nonces: vec![Uint64::new(2), Uint64::new(3)],
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&lister, &[]),
+            cancel_nonces_msg,
+        )
+        .unwrap();
+        match execute_offer(&mut deps, &key, 2) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NonceUsed { nonce: 2 }),
+        };
+        assert_eq!(
+            nonces(deps.as_ref(), &lister),
+            NoncesResponse {
+                min_nonce: Uint64::new(0),
+                used: vec![Uint64::new(1), Uint64::new(2), Uint64::new(3)],
+                next: None,
+            }
+        );
+        assert_eq!(nonce_status(deps.as_ref(), &lister, 2), NonceStatus::Used);
+        assert_eq!(nonce_status(deps.as_ref(), &lister, 4), NonceStatus::Usable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn increment_min_nonce() -> Result<(), String> {
+        // Initialization
+        let key = signing_key(1);
+        let lister = address(&key);
+        let owner = lister.clone();
+        let mut deps = mock_dependencies_with_owners(move |collection, _| match collection {
+            "goochi-goochi" => Some(String::from("responder")),
+            _ => Some(owner.clone()),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+        execute_offer(&mut deps, &key, 2).unwrap();
+
+        // Every older signature is void at once
+        let increment_min_nonce_msg = ExecuteMsg::IncrementMinNonce {
+            min_nonce: Uint64::new(5),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&lister, &[]),
+            increment_min_nonce_msg.clone(),
+        )
+        .unwrap();
+        match execute_offer(&mut deps, &key, 3) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::NonceBelowMinimum {
+                    nonce: 3,
+                    min_nonce: 5,
+                }
+            ),
+        };
+        assert_eq!(
+            nonce_status(deps.as_ref(), &lister, 3),
+            NonceStatus::BelowMinimum
+        );
+
+        // The minimum only goes up
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&lister, &[]),
+            increment_min_nonce_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::MinNonceNotIncreased { min_nonce: 5 }),
+        };
+
+        // Used nonces below the minimum are no longer listed
+        execute_offer(&mut deps, &key, 5).unwrap();
+        assert_eq!(
+            nonces(deps.as_ref(), &lister),
+            NoncesResponse {
+                min_nonce: Uint64::new(5),
+                used: vec![Uint64::new(5)],
+                next: None,
+            }
+        );
+
+        Ok(())
+    }
+}
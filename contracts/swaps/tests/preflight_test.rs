@@ -0,0 +1,144 @@
+mod common;
+
+use cosmwasm_std::Uint64;
+
+use swaps::contract::{execute, instantiate};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, mock_dependencies_with_owners, Cw721Deps};
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn initiate_swap(deps: &mut Cw721Deps, collection: &str) -> Result<(), ContractError> {
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from(collection),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn escrow_preflight_checks() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies_with_owners(|_, token_id| match token_id {
+            "123" => Some(String::from("swapper")),
+            "abc" => Some(String::from("someone")),
+            _ => None,
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+        deps.querier.not_cw721 = vec![String::from("bank")];
+
+        // Not a cw721 contract
+        match initiate_swap(&mut deps, "bank") {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::NotCw721Contract {
+                    collection: String::from("bank"),
+                }
+            ),
+        };
+
+        // Contract was never approved
+        deps.querier.token_approval = false;
+        match initiate_swap(&mut deps, "gp_collection") {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::ContractNotApproved {
+                    collection: String::from("gp_collection"),
+                    token_id: String::from("123"),
+                }
+            ),
+        };
+
+        // Approval as an operator is enough
+        deps.querier.operator_approval = true;
+        initiate_swap(&mut deps, "gp_collection").unwrap();
+
+        // Offering a token that doesn't exist
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("xyz"),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::TokenNotFound {
+                    collection: String::from("goochi-goochi"),
+                    token_id: String::from("xyz"),
+                }
+            ),
+        };
+
+        // Offering someone else's token
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::NotTokenOwner {
+                    collection: String::from("goochi-goochi"),
+                    token_id: String::from("abc"),
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn listing_someone_elses_token() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // "goochi-goochi" tokens belong to the responder
+        match initiate_swap(&mut deps, "goochi-goochi") {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::NotTokenOwner {
+                    collection: String::from("goochi-goochi"),
+                    token_id: String::from("123"),
+                }
+            ),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use cosmwasm_std::{to_binary, CosmosMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
+use swaps::state::{SwapAction, SwapStatus};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    #[test]
+    fn reject_offer() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        // Nothing to reject yet
+        let reject_offer_msg = ExecuteMsg::RejectOffer {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            reject_offer_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidSwapTransition {
+                    status: SwapStatus::Open,
+                    action: SwapAction::Reject,
+                }
+            ),
+        };
+
+        // Reply to Swap
+        let responder_info = mock_info("responder", &[]);
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info.clone(),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        // Only the lhs owner can reject
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info,
+            reject_offer_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        // Rejection returns the offered NFT and reopens the Swap
+        let res = execute(deps.as_mut(), mock_env(), swapper_info, reject_offer_msg).unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("goochi-goochi"),
+                funds: vec![],
+                msg: to_binary(&TransferNft {
+                    recipient: String::from("responder"),
+                    token_id: String::from("abc"),
+                })
+                .unwrap(),
+            })]
+        );
+
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        let get_swap_response = query(deps.as_ref(), mock_env(), get_swap_msg).unwrap();
+        let swap_response: SwapResponse = from_binary(&get_swap_response).unwrap();
+        assert_eq!(swap_response.status, SwapStatus::Open);
+        assert_eq!(swap_response.rhs, None);
+
+        Ok(())
+    }
+}
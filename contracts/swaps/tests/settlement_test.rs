@@ -0,0 +1,224 @@
+mod common;
+
+use cosmwasm_std::{Addr, ContractResult, Deps, Reply, ReplyOn, Response, Uint64};
+
+use swaps::contract::{execute, instantiate, query, reply};
+use swaps::error::ContractError;
+use swaps::msg::{
+    CollectionStatsResponse, EscrowedTokenResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+    StatsResponse, SwapResponse,
+};
+use swaps::state::{SwapAction, SwapRole, SwapStatus, LEDGER_NFTS};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn get_swap(deps: Deps) -> SwapResponse {
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        from_binary(&query(deps, mock_env(), get_swap_msg).unwrap()).unwrap()
+    }
+
+    fn is_escrowed(deps: Deps, collection: &str, token_id: &str) -> bool {
+        let escrowed_token_msg = QueryMsg::EscrowedToken {
+            collection: String::from(collection),
+            token_id: String::from(token_id),
+        };
+        let response: EscrowedTokenResponse =
+            from_binary(&query(deps, mock_env(), escrowed_token_msg).unwrap()).unwrap();
+        response.escrow.is_some()
+    }
+
+    /// Settles Swap 1 and returns the reply ids of its two transfers
+    fn settle(deps: &mut Cw721Deps) -> Vec<u64> {
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            finalize_swap_msg,
+        )
+        .unwrap();
+        assert!(res
+            .messages
+            .iter()
+            .all(|msg| msg.reply_on == ReplyOn::Error));
+        res.messages.iter().map(|msg| msg.id).collect()
+    }
+
+    fn fail_transfer(deps: &mut Cw721Deps, id: u64) -> Response {
+        let failure = Reply {
+            id,
+            result: ContractResult::Err(String::from("Collection is paused")),
+        };
+        reply(deps.as_mut(), mock_env(), failure).unwrap()
+    }
+
+    #[test]
+    fn retry_failed_transfer() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let reply_ids = settle(&mut deps);
+
+        // The listed NFT could not be delivered, the offered one was
+        let res = fail_transfer(&mut deps, reply_ids[0]);
+        assert_eq!(res.events[0].ty, "swap_settlement_failed");
+        let swap = get_swap(deps.as_ref());
+        assert_eq!(swap.status, SwapStatus::SettlementFailed);
+        assert_eq!(swap.failed_transfers, vec![SwapRole::Listing]);
+        assert!(is_escrowed(deps.as_ref(), "gp_collection", "123"));
+        assert!(!is_escrowed(deps.as_ref(), "goochi-goochi", "abc"));
+        let gp_collection = Addr::unchecked("gp_collection");
+        assert!(LEDGER_NFTS.has(&deps.storage, (&gp_collection, "123")));
+        let goochi_goochi = Addr::unchecked("goochi-goochi");
+        assert!(!LEDGER_NFTS.has(&deps.storage, (&goochi_goochi, "abc")));
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.completed, 0);
+        assert_eq!(stats.settlement_failed, 1);
+        assert_eq!(stats.escrowed_nfts, 1);
+
+        // The swapper already has the offered NFT, nothing can be refunded
+        let unwind_settlement_msg = ExecuteMsg::UnwindSettlement {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            unwind_settlement_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SettlementPartiallyDelivered {}),
+        };
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            cancel_swap_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidSwapTransition {
+                    status: SwapStatus::SettlementFailed,
+                    action: SwapAction::Cancel,
+                }
+            ),
+        };
+
+        // Only the parties can retry
+        let retry_settlement_msg = ExecuteMsg::RetrySettlement {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            retry_settlement_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        // Only the failed transfer is sent again
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            retry_settlement_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, reply_ids[0]);
+        let swap = get_swap(deps.as_ref());
+        assert_eq!(swap.status, SwapStatus::Completed);
+        assert_eq!(swap.failed_transfers, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unwind_failed_settlement() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let reply_ids = settle(&mut deps);
+
+        // Neither NFT could be delivered
+        for id in reply_ids {
+            fail_transfer(&mut deps, id);
+        }
+        let swap = get_swap(deps.as_ref());
+        assert_eq!(
+            swap.failed_transfers,
+            vec![SwapRole::Listing, SwapRole::Offer]
+        );
+
+        let unwind_settlement_msg = ExecuteMsg::UnwindSettlement {
+            swap_id: Uint64::new(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            unwind_settlement_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(get_swap(deps.as_ref()).status, SwapStatus::Cancelled);
+        assert!(!is_escrowed(deps.as_ref(), "gp_collection", "123"));
+
+        // An unwound swap was never settled
+        let collection_stats_msg = QueryMsg::CollectionStats {
+            collection: String::from("gp_collection"),
+        };
+        let response: CollectionStatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), collection_stats_msg).unwrap()).unwrap();
+        assert_eq!(response.settled, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use cosmwasm_std::{to_binary, CosmosMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
+use swaps::state::SwapStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn transfer(collection: &str, recipient: &str, token_id: &str) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: collection.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            })
+            .unwrap(),
+        })
+    }
+
+    #[test]
+    fn only_admin_can_shutdown() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        // Shutdown by someone else
+        let stranger_info = mock_info("stranger", &[]);
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            stranger_info,
+            ExecuteMsg::Shutdown {},
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        // Shutdown by admin
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            ExecuteMsg::Shutdown {},
+        )
+        .unwrap();
+
+        let config_response = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: ConfigResponse = from_binary(&config_response).unwrap();
+        assert!(config.shutdown);
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_new_swaps_after_shutdown() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg.clone(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            ExecuteMsg::Shutdown {},
+        )
+        .unwrap();
+
+        // Initiate Swap after shutdown
+        match execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::ContractShutdown {}),
+        };
+
+        // Reply to Swap after shutdown
+        let responder_info = mock_info("responder", &[]);
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        match execute(deps.as_mut(), mock_env(), responder_info, swap_reply_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::ContractShutdown {}),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn refund_all() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        // Refund before shutdown
+        let refund_all_msg = ExecuteMsg::RefundAll { limit: Some(2) };
+        let anyone_info = mock_info("anyone", &[]);
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            anyone_info.clone(),
+            refund_all_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::ContractNotShutdown {}),
+        };
+
+        // Initiate three Swaps, reply to the first one
+        for token_id in &["1", "2", "3"] {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: String::from(*token_id),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            ExecuteMsg::Shutdown {},
+        )
+        .unwrap();
+
+        // First page refunds both sides of the replied Swap and the second Swap
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            anyone_info.clone(),
+            refund_all_msg.clone(),
+        )
+        .unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                transfer("gp_collection", "swapper", "1"),
+                transfer("goochi-goochi", "responder", "abc"),
+                transfer("gp_collection", "swapper", "2"),
+            ]
+        );
+
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        let get_swap_response = query(deps.as_ref(), mock_env(), get_swap_msg).unwrap();
+        let swap_response: SwapResponse = from_binary(&get_swap_response).unwrap();
+        assert_eq!(swap_response.status, SwapStatus::Cancelled);
+
+        // Second page refunds only what is left
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            anyone_info.clone(),
+            refund_all_msg.clone(),
+        )
+        .unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(messages, vec![transfer("gp_collection", "swapper", "3")]);
+
+        // Nothing left to refund
+        let res = execute(deps.as_mut(), mock_env(), anyone_info, refund_all_msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use cosmwasm_std::{to_binary, Binary, CosmosMsg, Deps, SubMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{
+    ExecuteMsg, InstantiateMsg, OfferedNft, QueryMsg, SignedOffer, StatsResponse, SwapResponse,
+    WantedNft,
+};
+use swaps::state::{Custody, SwapStatus};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        address, mock_dependencies_with_owners, pubkey, sign, signing_key, Cw721Deps,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn offer(lister: &str) -> SignedOffer {
+        SignedOffer {
+            lister: String::from(lister),
+            give: OfferedNft {
+                collection: String::from("gp_collection"),
+                token_id: String::from("123"),
+            },
+            want: WantedNft {
+                collection: String::from("goochi-goochi"),
+                token_id: None,
+            },
+            expires: Expiration::AtHeight(mock_env().block.height + 100),
+            nonce: Uint64::new(1),
+            contract: mock_env().contract.address.to_string(),
+        }
+    }
+
+    fn setup(lister: &str) -> Cw721Deps {
+        let lister = String::from(lister);
+        let mut deps = mock_dependencies_with_owners(move |collection, _| match collection {
+            "goochi-goochi" => Some(String::from("responder")),
+            _ => Some(lister.clone()),
+        });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+        deps
+    }
+
+    fn execute_signed_offer(
+        deps: &mut Cw721Deps,
+        offer: SignedOffer,
+        signature: Binary,
+        pubkey: Binary,
+    ) -> Result<Vec<SubMsg>, ContractError> {
+        let execute_signed_offer_msg = ExecuteMsg::ExecuteSignedOffer {
+            offer,
+            token_id: String::from("abc"),
+            signature,
+            pubkey,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            execute_signed_offer_msg,
+        )
+        .map(|res| res.messages)
+    }
+
+    fn get_swap(deps: Deps) -> SwapResponse {
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        from_binary(&query(deps, mock_env(), get_swap_msg).unwrap()).unwrap()
+    }
+
+    fn transfer(collection: &str, token_id: &str, recipient: &str) -> SubMsg {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(collection),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: String::from(recipient),
+                token_id: String::from(token_id),
+            })
+            .unwrap(),
+        }))
+    }
+
+    #[test]
+    fn settle_signed_offer() -> Result<(), String> {
+        // Initialization
+        let key = signing_key(1);
+        let lister = address(&key);
+        let mut deps = setup(&lister);
+
+        let offer = offer(&lister);
+        let signature = sign(&key, &offer);
+        let messages = execute_signed_offer(&mut deps, offer, signature, pubkey(&key)).unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                transfer("gp_collection", "123", "responder"),
+                transfer("goochi-goochi", "abc", &lister),
+            ]
+        );
+
+        let swap = get_swap(deps.as_ref());
+        assert_eq!(swap.status, SwapStatus::Completed);
+        assert_eq!(swap.custody, Custody::Approval);
+        assert_eq!(swap.lhs.owner.as_str(), lister);
+
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.escrowed_nfts, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_offers() -> Result<(), String> {
+        // Initialization
+        let key = signing_key(1);
+        let lister = address(&key);
+        let mut deps = setup(&lister);
+
+        // Terms changed after signing
+        let mut tampered = offer(&lister);
+        let signature = sign(&key, &tampered);
+        tampered.give.token_id = String::from("456");
+        match execute_signed_offer(&mut deps, tampered, signature, pubkey(&key)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::InvalidSignature {}),
+        };
+
+        // Signed with someone else's key
+        let other = signing_key(2);
+        let signature = sign(&other, &offer(&lister));
+        match execute_signed_offer(&mut deps, offer(&lister), signature, pubkey(&other)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SignerMismatch {}),
+        };
+
+        // Meant for another deployment
+        let mut elsewhere = offer(&lister);
+        elsewhere.contract = String::from("other_swaps");
+        let signature = sign(&key, &elsewhere);
+        match execute_signed_offer(&mut deps, elsewhere, signature, pubkey(&key)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::WrongContract {}),
+        };
+
+        // Expired
+        let mut expired = offer(&lister);
+        expired.expires = Expiration::AtHeight(mock_env().block.height);
+        let signature = sign(&key, &expired);
+        match execute_signed_offer(&mut deps, expired, signature, pubkey(&key)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SwapExpired {}),
+        };
+
+        // A specific token is wanted
+        let mut specific = offer(&lister);
+        specific.want.token_id = Some(String::from("xyz"));
+        let signature = sign(&key, &specific);
+        match execute_signed_offer(&mut deps, specific, signature, pubkey(&key)) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::NotWantedToken {}),
+        };
+
+        Ok(())
+    }
+}
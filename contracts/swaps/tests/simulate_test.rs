@@ -0,0 +1,141 @@
+mod common;
+
+use cosmwasm_std::{attr, to_binary, Deps, SubMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SimulateResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn simulate(deps: Deps, sender: &str, msg: ExecuteMsg) -> SimulateResponse {
+        let simulate_msg = QueryMsg::Simulate {
+            sender: String::from(sender),
+            funds: vec![],
+            msg: Box::new(msg),
+        };
+        from_binary(&query(deps, mock_env(), simulate_msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn simulate_without_saving() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Simulated Swap creation
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        let response = simulate(deps.as_ref(), "swapper", create_swap_msg.clone());
+        assert_eq!(response.error, None);
+        assert_eq!(
+            response.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: String::from("gp_collection"),
+                funds: vec![],
+                msg: to_binary(&TransferNft {
+                    recipient: mock_env().contract.address.to_string(),
+                    token_id: String::from("123"),
+                })
+                .unwrap(),
+            })]
+        );
+        assert_eq!(
+            response.attributes,
+            vec![attr("method", "create_swap"), attr("swap_id", "1")]
+        );
+
+        // Nothing was saved
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(1),
+        };
+        assert!(query(deps.as_ref(), mock_env(), get_swap_msg).is_err());
+
+        // Simulated failure
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(1),
+        };
+        let response = simulate(deps.as_ref(), "anyone", cancel_swap_msg);
+        assert_eq!(response.error, Some(String::from("Unauthorized")));
+        assert_eq!(response.messages, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn simulate_reads_own_writes() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), creator_info.clone(), msg).unwrap();
+
+        // Initiate three Swaps and shutdown
+        for token_id in 1..=3 {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: token_id.to_string(),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("swapper", &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            creator_info,
+            ExecuteMsg::Shutdown {},
+        )
+        .unwrap();
+
+        // Refund progress written during the simulation is seen by the rest of it
+        let refund_all_msg = ExecuteMsg::RefundAll { limit: Some(2) };
+        let response = simulate(deps.as_ref(), "anyone", refund_all_msg.clone());
+        assert_eq!(
+            response.attributes,
+            vec![
+                attr("method", "refund_all"),
+                attr("refunded", "2"),
+                attr("done", "false"),
+            ]
+        );
+
+        // ...but not by the real execution
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            refund_all_msg,
+        )
+        .unwrap();
+        assert_eq!(res.attributes, response.attributes);
+
+        Ok(())
+    }
+}
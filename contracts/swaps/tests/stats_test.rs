@@ -0,0 +1,172 @@
+mod common;
+
+use cosmwasm_std::{Addr, Deps, Uint64};
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{
+    CollectionStatsResponse, ExecuteMsg, InstantiateMsg, ListSwapsResponse, QueryMsg, StatsResponse,
+};
+use swaps::state::SwapStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_dependencies, Cw721Deps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn stats(deps: Deps) -> StatsResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::Stats {}).unwrap()).unwrap()
+    }
+
+    fn settled(deps: Deps, collection: &str) -> u64 {
+        let collection_stats_msg = QueryMsg::CollectionStats {
+            collection: String::from(collection),
+        };
+        let response: CollectionStatsResponse =
+            from_binary(&query(deps, mock_env(), collection_stats_msg).unwrap()).unwrap();
+        assert_eq!(response.collection, Addr::unchecked(collection));
+        response.settled
+    }
+
+    /// Counters must match a recount over every stored Swap
+    fn assert_consistent(deps: Deps) {
+        let mut recount = StatsResponse {
+            open: 0,
+            offered: 0,
+            completed: 0,
+            cancelled: 0,
+            expired: 0,
+            settlement_failed: 0,
+            broken: 0,
+            escrowed_nfts: 0,
+        };
+        let mut start_after = None;
+        loop {
+            let list_swaps_msg = QueryMsg::ListSwaps {
+                start_after,
+                limit: None,
+                status: None,
+                owner: None,
+                collection: None,
+            };
+            let page: ListSwapsResponse =
+                from_binary(&query(deps, mock_env(), list_swaps_msg).unwrap()).unwrap();
+            for swap in page.swaps {
+                let held = match (swap.status, swap.rhs) {
+                    (SwapStatus::SettlementFailed, _) => swap.failed_transfers.len() as u64,
+                    (_, Some(_)) => 2,
+                    (_, None) => 1,
+                };
+                match swap.status {
+                    SwapStatus::Open => recount.open += 1,
+                    SwapStatus::Offered => recount.offered += 1,
+                    SwapStatus::Completed => recount.completed += 1,
+                    SwapStatus::Cancelled => recount.cancelled += 1,
+                    SwapStatus::Expired => recount.expired += 1,
+                    SwapStatus::SettlementFailed => recount.settlement_failed += 1,
+                    SwapStatus::Broken => recount.broken += 1,
+                }
+                if !swap.status.is_terminal() {
+                    recount.escrowed_nfts += held;
+                }
+            }
+            if page.next.is_none() {
+                break;
+            }
+            start_after = page.next;
+        }
+        assert_eq!(stats(deps), recount);
+    }
+
+    fn run(deps: &mut Cw721Deps, sender: &str, msg: ExecuteMsg, blocks: u64) {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        execute(deps.as_mut(), env, mock_info(sender, &[]), msg).unwrap();
+        assert_consistent(deps.as_ref());
+    }
+
+    #[test]
+    fn stats_follow_every_transition() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+        assert_consistent(deps.as_ref());
+
+        // Initiate Swaps 1 to 4, the last one expires in 10 blocks
+        for token_id in 1..=4 {
+            let expires = match token_id {
+                4 => Some(Expiration::AtHeight(mock_env().block.height + 10)),
+                _ => None,
+            };
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from("gp_collection"),
+                token_id: token_id.to_string(),
+                expires,
+                custody: None,
+            };
+            run(&mut deps, "swapper", create_swap_msg, 0);
+        }
+
+        // Offers on every Swap
+        for swap_id in 1..=4 {
+            let swap_reply_msg = ExecuteMsg::SwapReply {
+                swap_id: Uint64::new(swap_id),
+                collection: String::from("goochi-goochi"),
+                token_id: swap_id.to_string(),
+            };
+            run(&mut deps, "responder", swap_reply_msg, 0);
+        }
+
+        // Swap 1 completes, Swap 2 offer is rejected, Swap 3 is cancelled, Swap 4 expires
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        run(&mut deps, "swapper", finalize_swap_msg, 0);
+        let reject_offer_msg = ExecuteMsg::RejectOffer {
+            swap_id: Uint64::new(2),
+        };
+        run(&mut deps, "swapper", reject_offer_msg, 0);
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(3),
+        };
+        run(&mut deps, "swapper", cancel_swap_msg, 0);
+        let reclaim_msg = ExecuteMsg::ReclaimExpiredSwap {
+            swap_id: Uint64::new(4),
+        };
+        run(&mut deps, "anyone", reclaim_msg, 10);
+
+        assert_eq!(
+            stats(deps.as_ref()),
+            StatsResponse {
+                open: 1,
+                offered: 0,
+                completed: 1,
+                cancelled: 1,
+                expired: 1,
+                settlement_failed: 0,
+                broken: 0,
+                escrowed_nfts: 1,
+            }
+        );
+        assert_eq!(settled(deps.as_ref(), "gp_collection"), 1);
+        assert_eq!(settled(deps.as_ref(), "goochi-goochi"), 1);
+        assert_eq!(settled(deps.as_ref(), "other_collection"), 0);
+
+        // Refunds after shutdown cancel what is left
+        run(&mut deps, "creator", ExecuteMsg::Shutdown {}, 0);
+        let refund_all_msg = ExecuteMsg::RefundAll { limit: None };
+        run(&mut deps, "anyone", refund_all_msg, 0);
+        let stats = stats(deps.as_ref());
+        assert_eq!(stats.open, 0);
+        assert_eq!(stats.cancelled, 2);
+        assert_eq!(stats.escrowed_nfts, 0);
+
+        Ok(())
+    }
+}
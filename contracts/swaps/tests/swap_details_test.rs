@@ -0,0 +1,128 @@
+mod common;
+
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    from_slice, to_binary, Binary, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemResult, Uint64, WasmQuery,
+};
+use cw721::{ContractInfoResponse, Cw721QueryMsg};
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::msg::{
+    ExecuteMsg, InstantiateMsg, NftDetails, NftMetadata, QueryMsg, SwapDetailsResponse,
+};
+
+use common::Cw721Querier;
+
+/// Answers metadata queries for "gp_collection" only, any other collection just knows its owners
+struct CollectionQuerier(Cw721Querier);
+
+impl Querier for CollectionQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let (contract_addr, msg) = match from_slice(bin_request).unwrap() {
+            QueryRequest::<Empty>::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                (contract_addr, msg)
+            }
+            _ => panic!("Unexpected query"),
+        };
+        if contract_addr != "gp_collection" {
+            return self.0.raw_query(bin_request);
+        }
+        let response = match from_slice(&msg).unwrap() {
+            Cw721QueryMsg::ContractInfo {} => to_binary(&ContractInfoResponse {
+                name: String::from("Galactic Punks"),
+                symbol: String::from("GP"),
+            })
+            .unwrap(),
+            Cw721QueryMsg::NftInfo { .. } => Binary::from(
+                br#"{"token_uri":"ipfs://123","extension":{"name":"Punk \"123\"","traits":[{"x":1}]}}"#
+                    .to_vec(),
+            ),
+            _ => return self.0.raw_query(bin_request),
+        };
+        SystemResult::Ok(ContractResult::Ok(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    #[test]
+    fn swap_details() -> Result<(), String> {
+        // Initialization
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: CollectionQuerier(common::mock_dependencies().querier),
+        };
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        // Reply from a collection without metadata
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        let get_swap_details_msg = QueryMsg::GetSwapDetails {
+            swap_id: Uint64::new(1),
+        };
+        let response: SwapDetailsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), get_swap_details_msg).unwrap()).unwrap();
+
+        assert_eq!(response.swap.swap_id, Uint64::new(1));
+        assert_eq!(
+            response.lhs,
+            NftDetails {
+                collection: Some(ContractInfoResponse {
+                    name: String::from("Galactic Punks"),
+                    symbol: String::from("GP"),
+                }),
+                nft: Some(NftMetadata {
+                    token_uri: Some(String::from("ipfs://123")),
+                    extension: Some(Binary::from(
+                        br#"{"name":"Punk \"123\"","traits":[{"x":1}]}"#.to_vec()
+                    )),
+                }),
+            }
+        );
+        assert_eq!(
+            response.rhs,
+            Some(NftDetails {
+                collection: None,
+                nft: None,
+            })
+        );
+
+        Ok(())
+    }
+}
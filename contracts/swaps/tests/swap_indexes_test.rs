@@ -0,0 +1,137 @@
+mod common;
+
+use cosmwasm_std::{Order, Storage, Uint64};
+
+use swaps::contract::{execute, instantiate};
+use swaps::msg::{ExecuteMsg, InstantiateMsg};
+use swaps::state::{swap_id_from_key, swaps};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies_with_owners;
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn ids(storage: &dyn Storage, index: &str, prefix: Vec<u8>) -> Vec<u64> {
+        let swaps = swaps();
+        let prefix = match index {
+            "lhs_owner" => swaps.idx.lhs_owner.prefix(prefix),
+            "rhs_owner" => swaps.idx.rhs_owner.prefix(prefix),
+            "collection" => swaps.idx.collection.prefix(prefix),
+            "status" => swaps.idx.status.prefix(prefix),
+            "active_lhs_owner" => swaps.idx.active_lhs_owner.prefix(prefix),
+            "active_rhs_owner" => swaps.idx.active_rhs_owner.prefix(prefix),
+            _ => panic!("Unknown index"),
+        };
+        prefix
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| swap_id_from_key(&item.unwrap().0).unwrap())
+            .collect()
+    }
+
+    fn token_ids(storage: &dyn Storage, collection: &str, token_id: &str) -> Vec<u64> {
+        swaps()
+            .idx
+            .token
+            .prefix((collection.as_bytes().to_vec(), token_id.as_bytes().to_vec()))
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| swap_id_from_key(&item.unwrap().0).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn swaps_are_indexed() -> Result<(), String> {
+        // Initialization
+        let mut deps =
+            mock_dependencies_with_owners(|collection, token_id| match (collection, token_id) {
+                ("gp_collection", "2") => Some(String::from("bob")),
+                ("goochi-goochi", "xyz") => Some(String::from("carol")),
+                _ => Some(String::from("alice")),
+            });
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swaps
+        let listings = vec![
+            ("alice", "gp_collection", "1"),
+            ("bob", "gp_collection", "2"),
+            ("alice", "goochi-goochi", "abc"),
+        ];
+        for (owner, collection, token_id) in listings {
+            let create_swap_msg = ExecuteMsg::InitiateSwap {
+                collection: String::from(collection),
+                token_id: String::from(token_id),
+                expires: None,
+                custody: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                create_swap_msg,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(ids(&deps.storage, "lhs_owner", b"alice".to_vec()), [1, 3]);
+        assert_eq!(ids(&deps.storage, "lhs_owner", b"bob".to_vec()), [2]);
+        assert_eq!(
+            ids(&deps.storage, "collection", b"gp_collection".to_vec()),
+            [1, 2]
+        );
+        assert_eq!(token_ids(&deps.storage, "goochi-goochi", "abc"), [3]);
+        assert_eq!(
+            token_ids(&deps.storage, "goochi-goochi", "1"),
+            Vec::<u64>::new()
+        );
+        assert_eq!(ids(&deps.storage, "rhs_owner", vec![]), [1, 2, 3]);
+
+        // Reply moves the Swap to the responder in the rhs owner index
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(2),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("xyz"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("carol", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        assert_eq!(ids(&deps.storage, "rhs_owner", b"carol".to_vec()), [2]);
+        assert_eq!(ids(&deps.storage, "rhs_owner", vec![]), [1, 3]);
+        assert_eq!(ids(&deps.storage, "status", b"open".to_vec()), [1, 3]);
+        assert_eq!(ids(&deps.storage, "status", b"offered".to_vec()), [2]);
+        assert_eq!(
+            ids(&deps.storage, "active_rhs_owner", b"carol".to_vec()),
+            [2]
+        );
+
+        // Cancelling takes the Swap out of the active owner indexes only
+        let cancel_swap_msg = ExecuteMsg::CancelSwap {
+            swap_id: Uint64::new(1),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            cancel_swap_msg,
+        )
+        .unwrap();
+
+        assert_eq!(ids(&deps.storage, "lhs_owner", b"alice".to_vec()), [1, 3]);
+        assert_eq!(
+            ids(&deps.storage, "active_lhs_owner", b"alice".to_vec()),
+            [3]
+        );
+        assert_eq!(ids(&deps.storage, "active_lhs_owner", vec![]), [1]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use cosmwasm_std::{Deps, Env, Uint64};
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg, Permission, QueryMsg, SwapPermissionsResponse};
+use swaps::state::SwapStatus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn permissions(deps: Deps, env: Env, viewer: &str) -> SwapPermissionsResponse {
+        let swap_permissions_msg = QueryMsg::SwapPermissions {
+            swap_id: Uint64::new(1),
+            viewer: String::from(viewer),
+        };
+        from_binary(&query(deps, env, swap_permissions_msg).unwrap()).unwrap()
+    }
+
+    fn allowed() -> Permission {
+        Permission {
+            allowed: true,
+            reason: None,
+        }
+    }
+
+    fn denied(reason: &str) -> Permission {
+        Permission {
+            allowed: false,
+            reason: Some(String::from(reason)),
+        }
+    }
+
+    fn later_env(blocks: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        env
+    }
+
+    #[test]
+    fn swap_permissions() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap that expires in 10 blocks
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("swapper", &[]),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        let view = permissions(deps.as_ref(), mock_env(), "swapper");
+        assert_eq!(view.swap.status, SwapStatus::Open);
+        assert_eq!(view.cancel, allowed());
+        assert_eq!(view.accept, denied("SwapNotResponded"));
+        assert_eq!(view.withdraw, denied("Unauthorized"));
+        assert_eq!(view.reclaim, denied("SwapNotExpired"));
+        assert_eq!(view.respond, allowed());
+
+        // Reply to Swap
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("responder", &[]),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        let view = permissions(deps.as_ref(), mock_env(), "swapper");
+        assert_eq!(view.accept, allowed());
+        assert_eq!(view.reject, allowed());
+        assert_eq!(view.respond, denied("InvalidSwapTransition"));
+
+        let view = permissions(deps.as_ref(), mock_env(), "responder");
+        assert_eq!(view.cancel, denied("Unauthorized"));
+        assert_eq!(view.accept, denied("Unauthorized"));
+        assert_eq!(view.withdraw, allowed());
+
+        // After expiration only reclaiming is left
+        let view = permissions(deps.as_ref(), later_env(10), "anyone");
+        assert_eq!(view.accept, denied("Unauthorized"));
+        assert_eq!(view.reclaim, allowed());
+        assert_eq!(view.respond, denied("SwapExpired"));
+
+        let view = permissions(deps.as_ref(), later_env(10), "swapper");
+        assert_eq!(view.accept, denied("SwapExpired"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn withdraw_offer() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg,
+        )
+        .unwrap();
+
+        // Reply to Swap
+        let responder_info = mock_info("responder", &[]);
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info.clone(),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        // Only the responder can withdraw the offer
+        let withdraw_offer_msg = ExecuteMsg::WithdrawOffer {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            withdraw_offer_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        // Only the Swap owner can accept the offer
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info.clone(),
+            finalize_swap_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::Unauthorized {}),
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info,
+            withdraw_offer_msg,
+        )
+        .unwrap();
+
+        let view = permissions(deps.as_ref(), mock_env(), "swapper");
+        assert_eq!(view.swap.status, SwapStatus::Open);
+        assert_eq!(view.swap.rhs, None);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use cosmwasm_std::Uint64;
+
 use swaps::contract::{execute, instantiate};
 use swaps::error::ContractError;
 use swaps::msg::{ExecuteMsg, InstantiateMsg};
@@ -19,9 +21,9 @@ mod tests {
         let _res = instantiate(deps.as_mut(), mock_env(), creator_info, msg);
 
         // Reply to not existing Swap
-        let responder_info = mock_info("responder", &coins(2, "token"));
+        let responder_info = mock_info("responder", &[]);
         let swap_reply_msg = ExecuteMsg::SwapReply {
-            swap_id: "123".to_string(),
+            swap_id: Uint64::new(123),
             collection: String::from("goochi-goochi"),
             token_id: String::from("abc"),
         };
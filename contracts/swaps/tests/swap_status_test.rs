@@ -0,0 +1,241 @@
+mod common;
+
+use cosmwasm_std::{to_binary, CosmosMsg, Uint64, WasmMsg};
+use cw721::Cw721ExecuteMsg::TransferNft;
+use cw721::Expiration;
+
+use swaps::contract::{execute, instantiate, query};
+use swaps::error::ContractError;
+use swaps::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SwapResponse};
+use swaps::state::{SwapAction, SwapStatus};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Deps, Env};
+    #[allow(unused_imports)]
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn transfer(collection: &str, recipient: &str, token_id: &str) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: collection.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            })
+            .unwrap(),
+        })
+    }
+
+    fn swap_status(deps: Deps, swap_id: u64) -> SwapStatus {
+        let get_swap_msg = QueryMsg::GetSwap {
+            swap_id: Uint64::new(swap_id),
+        };
+        let get_swap_response = query(deps, mock_env(), get_swap_msg).unwrap();
+        let swap_response: SwapResponse = from_binary(&get_swap_response).unwrap();
+        swap_response.status
+    }
+
+    fn later_env(blocks: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        env
+    }
+
+    #[test]
+    fn completed_swap() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: None,
+            custody: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            create_swap_msg,
+        )
+        .unwrap();
+        assert_eq!(swap_status(deps.as_ref(), 1), SwapStatus::Open);
+
+        // Reply to Swap
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        let responder_info = mock_info("responder", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info.clone(),
+            swap_reply_msg,
+        )
+        .unwrap();
+        assert_eq!(swap_status(deps.as_ref(), 1), SwapStatus::Offered);
+
+        // Second reply would strand the first offer
+        let other_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("xyz"),
+        };
+        match execute(deps.as_mut(), mock_env(), responder_info, other_reply_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidSwapTransition {
+                    status: SwapStatus::Offered,
+                    action: SwapAction::Respond,
+                }
+            ),
+        };
+
+        // Finalize Swap exchanges the NFTs
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            swapper_info.clone(),
+            finalize_swap_msg.clone(),
+        )
+        .unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                transfer("gp_collection", "responder", "123"),
+                transfer("goochi-goochi", "swapper", "abc"),
+            ]
+        );
+        assert_eq!(swap_status(deps.as_ref(), 1), SwapStatus::Completed);
+
+        // Completed Swap is kept, but can't be finalized again
+        match execute(deps.as_mut(), mock_env(), swapper_info, finalize_swap_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(
+                err,
+                ContractError::InvalidSwapTransition {
+                    status: SwapStatus::Completed,
+                    action: SwapAction::Accept,
+                }
+            ),
+        };
+
+        Ok(())
+    }
+
+    #[test]
+    fn expired_swap() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Initiate Swap that expires in 10 blocks
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+            custody: None,
+        };
+        execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg).unwrap();
+
+        let swap_reply_msg = ExecuteMsg::SwapReply {
+            swap_id: Uint64::new(1),
+            collection: String::from("goochi-goochi"),
+            token_id: String::from("abc"),
+        };
+        let responder_info = mock_info("responder", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            responder_info.clone(),
+            swap_reply_msg,
+        )
+        .unwrap();
+
+        // Reclaim before expiration
+        let anyone_info = mock_info("anyone", &[]);
+        let reclaim_msg = ExecuteMsg::ReclaimExpiredSwap {
+            swap_id: Uint64::new(1),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            anyone_info.clone(),
+            reclaim_msg.clone(),
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SwapNotExpired {}),
+        };
+
+        // Finalize after expiration
+        let finalize_swap_msg = ExecuteMsg::FinalizeSwap {
+            swap_id: Uint64::new(1),
+        };
+        let swapper_info = mock_info("swapper", &[]);
+        match execute(
+            deps.as_mut(),
+            later_env(10),
+            swapper_info,
+            finalize_swap_msg,
+        ) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SwapExpired {}),
+        };
+
+        // Reclaim after expiration returns NFTs to depositors
+        let res = execute(deps.as_mut(), later_env(10), anyone_info, reclaim_msg).unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                transfer("gp_collection", "swapper", "123"),
+                transfer("goochi-goochi", "responder", "abc"),
+            ]
+        );
+        assert_eq!(swap_status(deps.as_ref(), 1), SwapStatus::Expired);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cant_initiate_expired_swap() -> Result<(), String> {
+        // Initialization
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        instantiate(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        let swapper_info = mock_info("swapper", &[]);
+        let create_swap_msg = ExecuteMsg::InitiateSwap {
+            collection: String::from("gp_collection"),
+            token_id: String::from("123"),
+            expires: Some(Expiration::AtHeight(mock_env().block.height)),
+            custody: None,
+        };
+        match execute(deps.as_mut(), mock_env(), swapper_info, create_swap_msg) {
+            Ok(_) => panic!("Error expected"),
+            Err(err) => assert_eq!(err, ContractError::SwapExpired {}),
+        };
+
+        Ok(())
+    }
+}